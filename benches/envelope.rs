@@ -0,0 +1,68 @@
+use std::hint::black_box;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use lastfm_rs_api::{
+    error::{last_fm::LastFmErrorResponse, response::ResponseSeed},
+    page::{Page, serde::PageSeed},
+    types::track::Track,
+};
+use serde::{Deserialize, de::DeserializeSeed};
+
+/// A `user.getrecenttracks` page of `len` extended tracks.
+fn recent_tracks(len: usize) -> String {
+    let tracks = (0..len)
+        .map(|i| {
+            format!(
+                r##"{{"artist":{{"url":"https://www.last.fm/music/Artist+{i}","name":"Artist {i}","image":[{{"size":"small","#text":"https://lastfm.freetls.fastly.net/i/u/34s/{i}.png"}},{{"size":"medium","#text":"https://lastfm.freetls.fastly.net/i/u/64s/{i}.png"}},{{"size":"large","#text":"https://lastfm.freetls.fastly.net/i/u/174s/{i}.png"}},{{"size":"extralarge","#text":"https://lastfm.freetls.fastly.net/i/u/300x300/{i}.png"}}],"mbid":""}},"date":{{"uts":"{uts}","#text":"01 Jan 2024, 00:00"}},"mbid":"","name":"Track {i}","image":[{{"size":"small","#text":"https://lastfm.freetls.fastly.net/i/u/34s/{i}.png"}},{{"size":"medium","#text":"https://lastfm.freetls.fastly.net/i/u/64s/{i}.png"}},{{"size":"large","#text":"https://lastfm.freetls.fastly.net/i/u/174s/{i}.png"}},{{"size":"extralarge","#text":"https://lastfm.freetls.fastly.net/i/u/300x300/{i}.png"}}],"streamable":"0","album":{{"mbid":"","#text":"Album {i}"}},"url":"https://www.last.fm/music/Artist+{i}/_/Track+{i}","loved":"0"}}"##,
+                uts = 1_700_000_000 + i,
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+
+    format!(
+        r#"{{"recenttracks":{{"track":[{tracks}],"@attr":{{"user":"rj","totalPages":"100","page":"1","perPage":"{len}","total":"{total}"}}}}}}"#,
+        total = len * 100,
+    )
+}
+
+/// The previous decoding: buffer the body, try it as an error, then decode it
+/// again as a page.
+fn buffered(bytes: &[u8]) -> Page<Track> {
+    let content: serde_json::Value = serde_json::from_slice(bytes).unwrap();
+
+    if LastFmErrorResponse::deserialize(&content).is_ok() {
+        panic!("not an error");
+    }
+
+    PageSeed::<Track>::new("recenttracks", "track")
+        .deserialize(content)
+        .unwrap()
+}
+
+fn single_pass(bytes: &[u8]) -> Page<Track> {
+    let mut de = serde_json::Deserializer::from_slice(bytes);
+
+    ResponseSeed::new(PageSeed::<Track>::new("recenttracks", "track"))
+        .deserialize(&mut de)
+        .unwrap()
+        .into_result()
+        .unwrap()
+}
+
+fn envelope(c: &mut Criterion) {
+    let body = recent_tracks(200);
+    assert_eq!(single_pass(body.as_bytes()).items.len(), 200);
+
+    let mut group = c.benchmark_group("recenttracks_200");
+    group.bench_function("buffered", |b| {
+        b.iter(|| buffered(black_box(body.as_bytes())))
+    });
+    group.bench_function("single_pass", |b| {
+        b.iter(|| single_pass(black_box(body.as_bytes())))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, envelope);
+criterion_main!(benches);
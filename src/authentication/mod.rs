@@ -1,9 +1,17 @@
 pub mod public;
+pub mod session;
 
 pub trait Capability {}
 pub enum ReadPublic {}
+pub enum ReadSession {}
 pub enum WriteUser {}
 impl Capability for ReadPublic {}
+impl Capability for ReadSession {}
 impl Capability for WriteUser {}
 
 pub trait Enables<C: Capability> {}
+
+/// An authentication component backed by a logged-in user's session.
+pub trait Session: Enables<ReadSession> {
+    fn username(&self) -> &str;
+}
@@ -0,0 +1,74 @@
+use std::sync::Arc;
+
+use reqwest::RequestBuilder;
+
+use crate::{
+    RequestComponent,
+    authentication::{Enables, ReadPublic, ReadSession, Session, WriteUser},
+};
+
+#[derive(Clone)]
+pub struct SessionAuthentication {
+    api_key: Arc<str>,
+    shared_secret: Arc<str>,
+    session_key: Arc<str>,
+    username: Arc<str>,
+}
+
+impl Enables<ReadPublic> for SessionAuthentication {}
+impl Enables<ReadSession> for SessionAuthentication {}
+impl Enables<WriteUser> for SessionAuthentication {}
+
+impl SessionAuthentication {
+    /// `session_key` and `username` are the `key` and `name` returned by
+    /// `auth.getSession`.
+    pub fn new(api_key: &str, shared_secret: &str, session_key: &str, username: &str) -> Self {
+        Self {
+            api_key: Arc::from(api_key),
+            shared_secret: Arc::from(shared_secret),
+            session_key: Arc::from(session_key),
+            username: Arc::from(username),
+        }
+    }
+}
+
+impl Session for SessionAuthentication {
+    fn username(&self) -> &str {
+        &self.username
+    }
+}
+
+impl RequestComponent for SessionAuthentication {
+    fn apply(&self, req: RequestBuilder) -> RequestBuilder {
+        req.query(&[("api_key", &self.api_key), ("sk", &self.session_key)])
+    }
+
+    /// A hash of the session key.
+    fn identity(&self) -> Option<String> {
+        Some(format!("{:x}", md5::compute(&*self.session_key)))
+    }
+
+    fn sign(&self, req: &mut reqwest::Request) {
+        let mut params: Vec<(String, String)> = req
+            .url()
+            .query_pairs()
+            .filter(|(k, _)| k != "format" && k != "callback")
+            .map(|(k, v)| (k.into_owned(), v.into_owned()))
+            .collect();
+
+        params.sort();
+
+        let mut raw = String::new();
+        for (k, v) in &params {
+            raw.push_str(k);
+            raw.push_str(v);
+        }
+        raw.push_str(&self.shared_secret);
+
+        let signature = format!("{:x}", md5::compute(raw));
+
+        req.url_mut()
+            .query_pairs_mut()
+            .append_pair("api_sig", &signature);
+    }
+}
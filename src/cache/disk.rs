@@ -0,0 +1,91 @@
+use std::{
+    fs, io,
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+use bytes::Bytes;
+
+use crate::cache::{Cache, CacheEntry};
+
+/// Keeps each response in a file of its own under a directory, so that it
+/// outlives the process.
+///
+/// Files are named after a hash of the key and hold the time they were stored
+/// on their first line. An entry is removed once it is read too old to use,
+/// and [`DiskCache::purge`] removes those that are never read again.
+///
+/// Every call reads or writes a file on the calling thread, which blocks the
+/// async runtime while it does. Keep the directory on fast local storage.
+pub struct DiskCache {
+    dir: PathBuf,
+}
+
+impl DiskCache {
+    /// Creates `dir` if it does not exist yet.
+    pub fn new(dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+
+        Ok(Self { dir })
+    }
+
+    /// Removes every entry stored at least `older_than` ago, e.g. from time to
+    /// time in a long-running process. Files that go away in the meantime are
+    /// skipped.
+    pub fn purge(&self, older_than: Duration) -> io::Result<()> {
+        for file in fs::read_dir(&self.dir)?.flatten() {
+            let Ok(modified) = file.metadata().and_then(|v| v.modified()) else {
+                continue;
+            };
+
+            if modified.elapsed().unwrap_or_default() >= older_than {
+                let _ = fs::remove_file(file.path());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{:x}.json", md5::compute(key)))
+    }
+}
+
+impl Cache for DiskCache {
+    fn get(&self, key: &str) -> Option<CacheEntry> {
+        let file = fs::read(self.path(key)).ok()?;
+        let newline = file.iter().position(|v| *v == b'\n')?;
+
+        let stored_at = std::str::from_utf8(&file[..newline]).ok()?.parse().ok()?;
+
+        Some(CacheEntry {
+            body: Bytes::from(file).slice(newline + 1..),
+            stored_at: SystemTime::UNIX_EPOCH + Duration::from_secs(stored_at),
+        })
+    }
+
+    /// Failing to write only means a miss later, so errors are ignored.
+    fn put(&self, key: &str, entry: CacheEntry) {
+        let stored_at = entry
+            .stored_at
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        let mut file = format!("{stored_at}\n").into_bytes();
+        file.extend_from_slice(&entry.body);
+
+        // Written aside first so that a reader never sees half a file.
+        let path = self.path(key);
+        let partial = path.with_extension(format!("{}.partial", fastrand::u64(..)));
+
+        if fs::write(&partial, file).is_ok() && fs::rename(&partial, &path).is_err() {
+            let _ = fs::remove_file(&partial);
+        }
+    }
+
+    fn remove(&self, key: &str) {
+        let _ = fs::remove_file(self.path(key));
+    }
+}
@@ -0,0 +1,42 @@
+use std::{num::NonZeroUsize, sync::Mutex};
+
+use lru::LruCache;
+
+use crate::cache::{Cache, CacheEntry};
+
+/// Keeps the `capacity` most recently used responses in memory.
+pub struct MemoryCache {
+    entries: Mutex<LruCache<String, CacheEntry>>,
+}
+
+impl MemoryCache {
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            entries: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+}
+
+impl Cache for MemoryCache {
+    fn get(&self, key: &str) -> Option<CacheEntry> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(key)
+            .cloned()
+    }
+
+    fn put(&self, key: &str, entry: CacheEntry) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .put(key.to_owned(), entry);
+    }
+
+    fn remove(&self, key: &str) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .pop(key);
+    }
+}
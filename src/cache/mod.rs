@@ -0,0 +1,146 @@
+pub mod disk;
+pub mod memory;
+
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use bytes::Bytes;
+
+use crate::request::LastFmRequest;
+
+/// Somewhere to keep response bodies between requests.
+///
+/// Calls are made from async code, so they should not block for long.
+pub trait Cache: Send + Sync {
+    fn get(&self, key: &str) -> Option<CacheEntry>;
+    fn put(&self, key: &str, entry: CacheEntry);
+
+    /// Called with the key of an entry that was found too old to use.
+    fn remove(&self, _key: &str) {}
+}
+
+#[derive(Debug, Clone)]
+pub struct CacheEntry {
+    pub body: Bytes,
+    pub stored_at: SystemTime,
+}
+
+impl CacheEntry {
+    pub fn new(body: Bytes) -> Self {
+        Self {
+            body,
+            stored_at: SystemTime::now(),
+        }
+    }
+
+    fn age(&self) -> Duration {
+        self.stored_at.elapsed().unwrap_or_default()
+    }
+}
+
+/// Which responses go into a [`Cache`], and for how long they are used.
+///
+/// Only `GET` calls to methods with a TTL are cached, under their
+/// [`LastFmRequest::canonical_key`]. Keys leave out the API key and signature
+/// but hold a hash of the session key, so sessions that share a cache never
+/// get each other's responses.
+#[derive(Clone)]
+pub struct CachePolicy {
+    cache: Arc<dyn Cache>,
+    ttls: HashMap<String, Duration>,
+    default_ttl: Option<Duration>,
+    stale_for: Duration,
+}
+
+impl CachePolicy {
+    pub fn new(cache: impl Cache + 'static) -> Self {
+        Self {
+            cache: Arc::new(cache),
+            ttls: HashMap::new(),
+            default_ttl: None,
+            stale_for: Duration::from_secs(24 * 60 * 60),
+        }
+    }
+
+    /// Caches responses of `method`, e.g. `artist.getInfo`, for `ttl`.
+    pub fn with_ttl(mut self, method: &str, ttl: Duration) -> Self {
+        self.ttls.insert(method.to_ascii_lowercase(), ttl);
+        self
+    }
+
+    /// Caches responses of every method without a TTL of its own for `ttl`.
+    pub fn with_default_ttl(mut self, ttl: Duration) -> Self {
+        self.default_ttl = Some(ttl);
+        self
+    }
+
+    /// How long past its TTL a response may still be used when Last.fm
+    /// reports that it is offline. A day by default.
+    pub fn with_stale_for(mut self, stale_for: Duration) -> Self {
+        self.stale_for = stale_for;
+        self
+    }
+
+    /// Looks up `request`, if its method is cached at all.
+    pub(crate) fn lookup(&self, request: &LastFmRequest) -> Option<Lookup> {
+        let method = request
+            .parameters()
+            .iter()
+            .find(|(k, _)| k == "method")
+            .map(|(_, v)| v.to_ascii_lowercase())?;
+
+        let ttl = self.ttls.get(&method).copied().or(self.default_ttl)?;
+        let key = request.canonical_key();
+        let mut entry = self.cache.get(&key);
+
+        if entry
+            .as_ref()
+            .is_some_and(|v| v.age() > ttl + self.stale_for)
+        {
+            self.cache.remove(&key);
+            entry = None;
+        }
+
+        Some(Lookup {
+            cache: self.cache.clone(),
+            key,
+            ttl,
+            stale_for: self.stale_for,
+            entry,
+        })
+    }
+}
+
+/// A call's place in the cache, and what was found there.
+pub(crate) struct Lookup {
+    cache: Arc<dyn Cache>,
+    key: String,
+    ttl: Duration,
+    stale_for: Duration,
+    entry: Option<CacheEntry>,
+}
+
+impl Lookup {
+    /// The cached body, if it is still within its TTL.
+    pub fn fresh(&self) -> Option<&Bytes> {
+        self.entry
+            .as_ref()
+            .filter(|v| v.age() <= self.ttl)
+            .map(|v| &v.body)
+    }
+
+    /// The cached body, if it is not too old to stand in for a response.
+    pub fn stale(&self) -> Option<&Bytes> {
+        self.entry
+            .as_ref()
+            .filter(|v| v.age() <= self.ttl + self.stale_for)
+            .map(|v| &v.body)
+    }
+
+    pub fn store(&self, body: Bytes) {
+        self.cache.put(&self.key, CacheEntry::new(body));
+    }
+}
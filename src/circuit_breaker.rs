@@ -0,0 +1,105 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::error::{Error, LastFmResult};
+
+/// When to stop sending requests to Last.fm while it is down.
+///
+/// After `failure_threshold` calls in a row fail because of an outage, the
+/// circuit opens and calls fail with [`Error::CircuitOpen`] without being sent.
+/// Once `cooldown` has passed a single call is let through as a probe: if it
+/// gets an answer the circuit closes again, otherwise it stays open for
+/// another `cooldown`.
+///
+/// Outages are [`ServiceOffline`] and [`TemporaryError`] responses, failed
+/// connections and timeouts.
+///
+/// [`ServiceOffline`]: crate::error::last_fm::LastFmError::ServiceOffline
+/// [`TemporaryError`]: crate::error::last_fm::LastFmError::TemporaryError
+#[derive(Debug, Clone, Copy)]
+pub struct CircuitBreakerPolicy {
+    pub failure_threshold: u32,
+    pub cooldown: Duration,
+}
+
+impl Default for CircuitBreakerPolicy {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+/// The state of the circuit, shared by every clone of the client it was made
+/// for.
+#[derive(Clone)]
+pub(crate) struct CircuitBreaker {
+    policy: CircuitBreakerPolicy,
+    state: Arc<Mutex<State>>,
+}
+
+enum State {
+    Closed {
+        failures: u32,
+    },
+    Open {
+        until: Instant,
+    },
+    /// A probe went out at `since`. Another one may go out if it has not come
+    /// back within the cooldown, e.g. because its caller gave up on it.
+    HalfOpen {
+        since: Instant,
+    },
+}
+
+impl CircuitBreaker {
+    pub fn new(policy: CircuitBreakerPolicy) -> Self {
+        Self {
+            policy,
+            state: Arc::new(Mutex::new(State::Closed { failures: 0 })),
+        }
+    }
+
+    /// Whether a call may be sent now.
+    pub fn admit(&self) -> LastFmResult<()> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+
+        let retry_in = match *state {
+            State::Closed { .. } => return Ok(()),
+            State::Open { until } => until.saturating_duration_since(now),
+            State::HalfOpen { since } => {
+                (since + self.policy.cooldown).saturating_duration_since(now)
+            }
+        };
+
+        if retry_in.is_zero() {
+            *state = State::HalfOpen { since: now };
+            return Ok(());
+        }
+
+        Err(Error::CircuitOpen { retry_in })
+    }
+
+    /// Takes into account whether a request sent after
+    /// [`CircuitBreaker::admit`] met an outage. Requests that several calls
+    /// share are recorded once.
+    pub fn record(&self, outage: bool) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+
+        *state = match (&*state, outage) {
+            (_, false) => State::Closed { failures: 0 },
+            (State::Closed { failures }, true) if failures + 1 < self.policy.failure_threshold => {
+                State::Closed {
+                    failures: failures + 1,
+                }
+            }
+            (_, true) => State::Open {
+                until: Instant::now() + self.policy.cooldown,
+            },
+        };
+    }
+}
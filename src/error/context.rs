@@ -0,0 +1,68 @@
+use std::fmt;
+
+use reqwest::StatusCode;
+
+/// Parameters whose values never show up in a [`RequestContext`].
+const REDACTED_PARAMETERS: &[&str] =
+    &["api_key", "sk", "api_sig", "token", "password", "authToken"];
+
+/// What was being sent when an [`Error`](super::Error) happened.
+#[derive(Debug, Clone)]
+pub struct RequestContext {
+    /// The Last.fm method, e.g. `user.getrecenttracks`.
+    pub method: String,
+
+    /// The parameters of the call, with secrets replaced by `REDACTED`.
+    pub parameters: Vec<(String, String)>,
+
+    pub page: Option<usize>,
+
+    /// The status of the response, if one came back.
+    pub status: Option<StatusCode>,
+}
+
+impl RequestContext {
+    pub(crate) fn new(parameters: &[(String, String)], status: Option<StatusCode>) -> Self {
+        let mut context = RequestContext {
+            method: String::new(),
+            parameters: Vec::new(),
+            page: None,
+            status,
+        };
+
+        for (k, v) in parameters {
+            match k.as_str() {
+                "method" => context.method = v.clone(),
+                "format" => {}
+                "page" => context.page = v.parse().ok(),
+                k if REDACTED_PARAMETERS.contains(&k) => context
+                    .parameters
+                    .push((k.to_owned(), "REDACTED".to_owned())),
+                _ => context.parameters.push((k.clone(), v.clone())),
+            }
+        }
+
+        context
+    }
+}
+
+impl fmt::Display for RequestContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.method)?;
+
+        if let Some(page) = self.page {
+            write!(f, " page {page}")?;
+        }
+
+        if let Some(status) = self.status {
+            write!(f, ", HTTP {}", status.as_u16())?;
+        }
+
+        for (i, (k, v)) in self.parameters.iter().enumerate() {
+            let separator = if i == 0 { ", with " } else { ", " };
+            write!(f, "{separator}{k}={v:?}")?;
+        }
+
+        Ok(())
+    }
+}
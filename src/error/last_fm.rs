@@ -56,12 +56,45 @@ pub enum LastFmError {
     #[error("Invalid method signature (code 13){message}")]
     InvalidSignature { message: MaybeMsg },
 
+    #[error("Unauthorized token (code 14){message}")]
+    UnauthorizedToken { message: MaybeMsg },
+
+    #[error("Token expired (code 15){message}")]
+    TokenExpired { message: MaybeMsg },
+
     #[error("Temporary error (code 16){message}")]
     TemporaryError { message: MaybeMsg },
 
+    #[error("Login required (code 17){message}")]
+    LoginRequired { message: MaybeMsg },
+
+    #[error("Trial expired (code 18){message}")]
+    TrialExpired { message: MaybeMsg },
+
+    #[error("Not enough content (code 20){message}")]
+    NotEnoughContent { message: MaybeMsg },
+
+    #[error("Not enough members (code 21){message}")]
+    NotEnoughMembers { message: MaybeMsg },
+
+    #[error("Not enough fans (code 22){message}")]
+    NotEnoughFans { message: MaybeMsg },
+
+    #[error("Not enough neighbours (code 23){message}")]
+    NotEnoughNeighbours { message: MaybeMsg },
+
+    #[error("No peak radio (code 24){message}")]
+    NoPeakRadio { message: MaybeMsg },
+
+    #[error("Radio not found (code 25){message}")]
+    RadioNotFound { message: MaybeMsg },
+
     #[error("Suspended API key (code 26){message}")]
     SuspendedApiKey { message: MaybeMsg },
 
+    #[error("Deprecated (code 27){message}")]
+    Deprecated { message: MaybeMsg },
+
     #[error("Rate limit exceeded (code 29){message}")]
     RateLimitExceeded { message: MaybeMsg },
 
@@ -83,12 +116,121 @@ impl LastFmError {
             Self::InvalidApiKey { .. } => 10,
             Self::ServiceOffline { .. } => 11,
             Self::InvalidSignature { .. } => 13,
+            Self::UnauthorizedToken { .. } => 14,
+            Self::TokenExpired { .. } => 15,
             Self::TemporaryError { .. } => 16,
+            Self::LoginRequired { .. } => 17,
+            Self::TrialExpired { .. } => 18,
+            Self::NotEnoughContent { .. } => 20,
+            Self::NotEnoughMembers { .. } => 21,
+            Self::NotEnoughFans { .. } => 22,
+            Self::NotEnoughNeighbours { .. } => 23,
+            Self::NoPeakRadio { .. } => 24,
+            Self::RadioNotFound { .. } => 25,
             Self::SuspendedApiKey { .. } => 26,
+            Self::Deprecated { .. } => 27,
             Self::RateLimitExceeded { .. } => 29,
             Self::Unknown { code, .. } => *code,
         }
     }
+
+    /// What whoever is using the application can do about the error.
+    pub fn hint(&self) -> &'static str {
+        match self {
+            Self::InvalidService { .. }
+            | Self::InvalidMethod { .. }
+            | Self::InvalidFormat { .. }
+            | Self::InvalidParameters { .. } => {
+                "The application sent a request Last.fm does not understand."
+            }
+            Self::InvalidResource { .. } => "That artist, album, track or user does not exist.",
+            Self::OperationFailed { .. }
+            | Self::ServiceOffline { .. }
+            | Self::TemporaryError { .. } => "Last.fm is having trouble. Try again later.",
+            Self::AuthenticationFailed { .. } | Self::InvalidSessionKey { .. } => {
+                "Your Last.fm session is no longer valid. Sign in again."
+            }
+            Self::InvalidApiKey { .. } | Self::InvalidSignature { .. } => {
+                "The application's Last.fm API key or secret is wrong."
+            }
+            Self::SuspendedApiKey { .. } => "The application's Last.fm API key has been suspended.",
+            Self::UnauthorizedToken { .. } => {
+                "Approve the application on Last.fm before continuing."
+            }
+            Self::TokenExpired { .. } => "The sign-in took too long. Start it again.",
+            Self::LoginRequired { .. } => {
+                "This profile is private. Sign in as its owner to see it."
+            }
+            Self::TrialExpired { .. } => "Your Last.fm trial has expired.",
+            Self::NotEnoughContent { .. }
+            | Self::NotEnoughMembers { .. }
+            | Self::NotEnoughFans { .. }
+            | Self::NotEnoughNeighbours { .. } => {
+                "There is not enough listening data for this yet."
+            }
+            Self::NoPeakRadio { .. } | Self::RadioNotFound { .. } => {
+                "That radio station is not available."
+            }
+            Self::Deprecated { .. } => "Last.fm no longer supports this feature.",
+            Self::RateLimitExceeded { .. } => "Too many requests. Wait a moment and try again.",
+            Self::Unknown { .. } => "Something went wrong on Last.fm.",
+        }
+    }
+
+    /// Whether Last.fm expects the same call to go through later.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::OperationFailed { .. }
+                | Self::ServiceOffline { .. }
+                | Self::TemporaryError { .. }
+                | Self::RateLimitExceeded { .. }
+        )
+    }
+
+    /// Whether Last.fm is down, rather than turning down the call.
+    pub(crate) fn is_outage(&self) -> bool {
+        matches!(
+            self,
+            Self::ServiceOffline { .. } | Self::TemporaryError { .. }
+        )
+    }
+
+    pub fn is_auth_error(&self) -> bool {
+        matches!(
+            self,
+            Self::AuthenticationFailed { .. }
+                | Self::InvalidSessionKey { .. }
+                | Self::InvalidApiKey { .. }
+                | Self::InvalidSignature { .. }
+                | Self::SuspendedApiKey { .. }
+                | Self::UnauthorizedToken { .. }
+                | Self::TokenExpired { .. }
+        )
+    }
+
+    /// Whether the call itself is at fault, which includes
+    /// [`LastFmError::is_auth_error`].
+    pub fn is_client_error(&self) -> bool {
+        self.is_auth_error()
+            || matches!(
+                self,
+                Self::InvalidService { .. }
+                    | Self::InvalidMethod { .. }
+                    | Self::InvalidFormat { .. }
+                    | Self::InvalidParameters { .. }
+                    | Self::InvalidResource { .. }
+                    | Self::LoginRequired { .. }
+                    | Self::TrialExpired { .. }
+                    | Self::NotEnoughContent { .. }
+                    | Self::NotEnoughMembers { .. }
+                    | Self::NotEnoughFans { .. }
+                    | Self::NotEnoughNeighbours { .. }
+                    | Self::NoPeakRadio { .. }
+                    | Self::RadioNotFound { .. }
+                    | Self::Deprecated { .. }
+            )
+    }
 }
 
 impl From<LastFmErrorResponse> for LastFmError {
@@ -106,8 +248,19 @@ impl From<LastFmErrorResponse> for LastFmError {
             10 => Self::InvalidApiKey { message: msg },
             11 => Self::ServiceOffline { message: msg },
             13 => Self::InvalidSignature { message: msg },
+            14 => Self::UnauthorizedToken { message: msg },
+            15 => Self::TokenExpired { message: msg },
             16 => Self::TemporaryError { message: msg },
+            17 => Self::LoginRequired { message: msg },
+            18 => Self::TrialExpired { message: msg },
+            20 => Self::NotEnoughContent { message: msg },
+            21 => Self::NotEnoughMembers { message: msg },
+            22 => Self::NotEnoughFans { message: msg },
+            23 => Self::NotEnoughNeighbours { message: msg },
+            24 => Self::NoPeakRadio { message: msg },
+            25 => Self::RadioNotFound { message: msg },
             26 => Self::SuspendedApiKey { message: msg },
+            27 => Self::Deprecated { message: msg },
             29 => Self::RateLimitExceeded { message: msg },
             other => Self::Unknown {
                 code: other,
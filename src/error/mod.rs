@@ -1,9 +1,14 @@
+pub mod context;
 pub mod last_fm;
+pub mod parse;
 pub mod response;
 
+use std::{sync::Arc, time::Duration};
+
+use reqwest::StatusCode;
 use thiserror::Error;
 
-use crate::error::last_fm::LastFmError;
+use crate::error::{context::RequestContext, last_fm::LastFmError, parse::ParseError};
 
 pub type LastFmResult<T> = std::result::Result<T, Error>;
 
@@ -16,5 +21,121 @@ pub enum Error {
     ApiError(#[from] LastFmError),
 
     #[error("Failed to parse: {0}")]
-    ParseError(#[from] serde_json::Error),
+    ParseError(#[from] ParseError),
+
+    /// A response with an error status and a body that is not a Last.fm
+    /// error.
+    #[error("HTTP {0}")]
+    HttpStatus(StatusCode),
+
+    #[error("Page size {page_size} is out of range for {method} (1 to {max})")]
+    InvalidPageSize {
+        method: String,
+        page_size: usize,
+        max: usize,
+    },
+
+    /// Another error, along with the request it came from.
+    #[error("{source} [{context}]")]
+    Context {
+        context: Box<RequestContext>,
+        source: Box<Error>,
+    },
+
+    /// The call was not sent as Last.fm has been failing, see
+    /// [`CircuitBreakerPolicy`](crate::circuit_breaker::CircuitBreakerPolicy).
+    #[error("Not sent as Last.fm seems to be down, retrying in {retry_in:?}")]
+    CircuitOpen { retry_in: Duration },
+
+    /// The error of a request that was shared with other callers.
+    #[error("{0}")]
+    Shared(Arc<Error>),
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(value: serde_json::Error) -> Self {
+        Error::ParseError(value.into())
+    }
+}
+
+impl Error {
+    /// The error without the [`Error::Context`] or [`Error::Shared`] around
+    /// it, for matching on.
+    pub fn inner(&self) -> &Error {
+        match self {
+            Error::Context { source, .. } => source.inner(),
+            Error::Shared(source) => source.inner(),
+            other => other,
+        }
+    }
+
+    pub fn context(&self) -> Option<&RequestContext> {
+        match self {
+            Error::Context { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// Whether sending the same request again might succeed: a transient
+    /// Last.fm error, HTTP 5xx, a timeout or a failed connection.
+    pub fn is_retryable(&self) -> bool {
+        match self.inner() {
+            Error::RequestFailed(e) if e.is_connect() || e.is_timeout() => true,
+            Error::ApiError(e) => e.is_retryable(),
+            other => other.status().is_some_and(|v| v.is_server_error()),
+        }
+    }
+
+    /// Whether the API key, session or signature was rejected.
+    pub fn is_auth_error(&self) -> bool {
+        match self.inner() {
+            Error::ApiError(e) => e.is_auth_error(),
+            other => other
+                .status()
+                .is_some_and(|v| v == StatusCode::UNAUTHORIZED || v == StatusCode::FORBIDDEN),
+        }
+    }
+
+    /// Whether the request itself is at fault, which includes
+    /// [`Error::is_auth_error`]. Sending it again will fail the same way.
+    pub fn is_client_error(&self) -> bool {
+        match self.inner() {
+            Error::ApiError(e) => e.is_client_error(),
+            Error::InvalidPageSize { .. } => true,
+            other => other
+                .status()
+                .is_some_and(|v| v.is_client_error() && v != StatusCode::TOO_MANY_REQUESTS),
+        }
+    }
+
+    /// Whether the error suggests that Last.fm is down, rather than that
+    /// something is wrong with the call.
+    pub(crate) fn is_outage(&self) -> bool {
+        match self.inner() {
+            Error::RequestFailed(e) => e.is_connect() || e.is_timeout(),
+            Error::ApiError(e) => e.is_outage(),
+            _ => false,
+        }
+    }
+
+    /// The HTTP status the error stems from, if it stems from one.
+    fn status(&self) -> Option<StatusCode> {
+        match self {
+            Error::RequestFailed(e) => e.status(),
+            Error::HttpStatus(status) => Some(*status),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn with_context(self, context: RequestContext) -> Self {
+        let source = match self {
+            Error::Context { source, .. } => *source,
+            other => other,
+        };
+
+        Error::Context {
+            context: Box::new(context),
+            source: Box::new(source),
+        }
+    }
 }
@@ -0,0 +1,80 @@
+use std::fmt;
+
+use reqwest::StatusCode;
+
+/// How much of a body a [`ParseError`] keeps.
+const MAX_BODY_LEN: usize = 512;
+
+/// A response that did not have the expected shape.
+#[derive(Debug)]
+pub struct ParseError {
+    pub source: serde_json::Error,
+
+    /// Where in the document the failing value sits, e.g.
+    /// `recenttracks.track[3].date.uts`. Empty when the document itself is at
+    /// fault, e.g. when it is not JSON at all.
+    pub path: String,
+
+    /// The start of the body, cut off after 512 bytes.
+    pub body: Option<String>,
+
+    pub status: Option<StatusCode>,
+}
+
+impl ParseError {
+    pub(crate) fn new(source: serde_json::Error, path: String, body: &[u8]) -> Self {
+        let mut kept = String::from_utf8_lossy(&body[..body.len().min(MAX_BODY_LEN)]).into_owned();
+        if body.len() > MAX_BODY_LEN {
+            kept.push('…');
+        }
+
+        Self {
+            source,
+            path: if path == "." { String::new() } else { path },
+            body: Some(kept),
+            status: None,
+        }
+    }
+
+    pub(crate) fn with_status(mut self, status: StatusCode) -> Self {
+        self.status = Some(status);
+        self
+    }
+}
+
+impl From<serde_json::Error> for ParseError {
+    fn from(source: serde_json::Error) -> Self {
+        Self {
+            source,
+            path: String::new(),
+            body: None,
+            status: None,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.path.is_empty() {
+            write!(f, "at {}: ", self.path)?;
+        }
+
+        write!(f, "{}", self.source)?;
+
+        if let Some(status) = self.status {
+            write!(f, " (HTTP {})", status.as_u16())?;
+        }
+
+        if let Some(body) = &self.body {
+            write!(f, " in {body:?}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
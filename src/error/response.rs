@@ -1,11 +1,17 @@
-use std::marker::PhantomData;
+use std::{borrow::Cow, fmt, marker::PhantomData};
 
 use serde::{
     Deserialize, Deserializer,
-    de::{self, DeserializeSeed},
+    de::{
+        self, DeserializeSeed, IgnoredAny, MapAccess, Visitor,
+        value::{CowStrDeserializer, MapAccessDeserializer},
+    },
 };
 
-use crate::error::last_fm::LastFmErrorResponse;
+use crate::error::{
+    last_fm::{LastFmError, LastFmErrorResponse},
+    parse::ParseError,
+};
 
 #[derive(Debug, Deserialize)]
 #[serde(untagged)]
@@ -33,7 +39,7 @@ impl<S: for<'de> DeserializeSeed<'de, Value = T>, T> ResponseSeed<T, S> {
     pub fn new(ok_seed: S) -> Self {
         Self {
             ok_seed,
-            _ph: PhantomData::default(),
+            _ph: PhantomData,
         }
     }
 }
@@ -44,16 +50,134 @@ impl<'da, S: for<'de> DeserializeSeed<'de, Value = T>, T> DeserializeSeed<'da>
     type Value = Response<T>;
 
     fn deserialize<D: Deserializer<'da>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
-        let content = serde_content::Value::deserialize(deserializer)?;
+        deserializer.deserialize_map(EnvelopeVisitor {
+            ok_seed: self.ok_seed,
+        })
+    }
+}
+
+/// Top-level keys that only an error body starts with.
+const ERROR_KEYS: &[&str] = &["error", "message", "links"];
+
+/// Tells an error body from any other by its first key, and hands the whole
+/// map to the matching seed without buffering it.
+struct EnvelopeVisitor<S> {
+    ok_seed: S,
+}
+
+impl<'de, S: DeserializeSeed<'de>> Visitor<'de> for EnvelopeVisitor<S> {
+    type Value = Response<S::Value>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a Last.fm response object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let first = map.next_key::<Cow<'de, str>>()?;
 
-        let err_de = serde_content::Deserializer::new(content.clone());
-        if let Ok(err_value) = LastFmErrorResponse::deserialize(err_de) {
-            return Ok(Response::Err(err_value));
+        if let Some(key) = &first
+            && ERROR_KEYS.contains(&key.as_ref())
+        {
+            return visit_error(first, map).map(Response::Err);
         }
 
         self.ok_seed
-            .deserialize(serde_content::Deserializer::new(content))
+            .deserialize(MapAccessDeserializer::new(Replay { first, map }))
             .map(Response::Ok)
-            .map_err(de::Error::custom)
     }
 }
+
+fn visit_error<'de, A: MapAccess<'de>>(
+    mut key: Option<Cow<'de, str>>,
+    mut map: A,
+) -> Result<LastFmErrorResponse, A::Error> {
+    let mut error = None;
+    let mut message = None;
+
+    while let Some(k) = key {
+        match k.as_ref() {
+            "error" => error = Some(map.next_value()?),
+            "message" => message = map.next_value()?,
+            _ => {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+
+        key = map.next_key()?;
+    }
+
+    Ok(LastFmErrorResponse {
+        message,
+        error: error.ok_or_else(|| de::Error::missing_field("error"))?,
+    })
+}
+
+/// A map whose first key has already been read, which it hands out again.
+struct Replay<'de, A> {
+    first: Option<Cow<'de, str>>,
+    map: A,
+}
+
+impl<'de, A: MapAccess<'de>> MapAccess<'de> for Replay<'de, A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        match self.first.take() {
+            Some(key) => seed.deserialize(CowStrDeserializer::new(key)).map(Some),
+            None => self.map.next_key_seed(seed),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.map.next_value_seed(seed)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.map
+            .size_hint()
+            .map(|v| v + usize::from(self.first.is_some()))
+    }
+}
+
+/// The Last.fm error `bytes` holds, if any. Any other body is only read up to
+/// its first key.
+pub(crate) fn api_error(bytes: &[u8]) -> Option<LastFmError> {
+    let mut de = serde_json::Deserializer::from_slice(bytes);
+
+    match ResponseSeed::new(NotAnError).deserialize(&mut de) {
+        Ok(Response::Err(e)) => Some(e.into()),
+        _ => None,
+    }
+}
+
+/// Turns down any body that gets to it, which ends decoding early.
+struct NotAnError;
+
+impl<'de> DeserializeSeed<'de> for NotAnError {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, _: D) -> Result<(), D::Error> {
+        Err(de::Error::custom("not an error"))
+    }
+}
+
+/// Decodes a response body with `seed`, keeping enough of a failure to tell
+/// what went wrong from the error alone.
+pub(crate) fn decode<S, T>(bytes: &[u8], seed: S) -> Result<Response<T>, ParseError>
+where
+    S: for<'de> DeserializeSeed<'de, Value = T>,
+{
+    let mut de = serde_json::Deserializer::from_slice(bytes);
+    let mut track = serde_path_to_error::Track::new();
+
+    ResponseSeed::new(seed)
+        .deserialize(serde_path_to_error::Deserializer::new(&mut de, &mut track))
+        .and_then(|v| de.end().map(|_| v))
+        .map_err(|e| ParseError::new(e, track.path().to_string(), bytes))
+}
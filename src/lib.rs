@@ -1,13 +1,37 @@
+use std::sync::Arc;
+
 use reqwest::{Method, RequestBuilder};
 
+use crate::{
+    cache::CachePolicy,
+    circuit_breaker::{CircuitBreaker, CircuitBreakerPolicy},
+    rate_limit::{Priority, RateLimit, RateLimiter},
+    request::{LastFmRequest, Policies, coalesce::Coalescer},
+    retry::RetryPolicy,
+};
+
 pub mod authentication;
+pub mod cache;
+pub mod circuit_breaker;
 pub mod error;
 pub mod page;
+pub mod rate_limit;
 pub mod request;
+pub mod retry;
 pub mod types;
 
-pub trait RequestComponent: Send + Sync + Clone {
+pub trait RequestComponent: Send + Sync + Clone + 'static {
     fn apply(&self, req: RequestBuilder) -> RequestBuilder;
+
+    /// Called on the built request right before it is sent, once every
+    /// parameter is in place.
+    fn sign(&self, _req: &mut reqwest::Request) {}
+
+    /// Tells apart the users that calls are made as, for calls whose response
+    /// depends on who is asking. Must not give away any secret.
+    fn identity(&self) -> Option<String> {
+        None
+    }
 }
 
 impl RequestComponent for () {
@@ -16,9 +40,13 @@ impl RequestComponent for () {
     }
 }
 
+const DEFAULT_BASE_URL: &str = "https://ws.audioscrobbler.com/2.0/";
+
 #[derive(Clone)]
 pub struct LastFm<T: RequestComponent> {
     client: reqwest::Client,
+    base_url: Arc<str>,
+    policies: Policies,
     authentication_component: T,
 }
 
@@ -26,32 +54,107 @@ impl LastFm<()> {
     pub fn new() -> Self {
         Self {
             client: Default::default(),
+            base_url: Arc::from(DEFAULT_BASE_URL),
+            policies: Policies {
+                rate_limiter: Some(RateLimiter::new(RateLimit::default())),
+                coalescer: Some(Coalescer::default()),
+                ..Default::default()
+            },
             authentication_component: (),
         }
     }
 }
 
+impl Default for LastFm<()> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T: RequestComponent> LastFm<T> {
     pub fn with_client(mut self, c: reqwest::Client) -> Self {
         self.client = c;
         self
     }
 
+    /// Sends requests somewhere other than `ws.audioscrobbler.com`, such as a
+    /// proxy or a mock server.
+    pub fn with_base_url(mut self, url: &str) -> Self {
+        self.base_url = Arc::from(url);
+        self
+    }
+
+    /// Replaces the [`RateLimit::default`] every request waits on. The limit
+    /// is shared with the clones made after this, not with earlier ones.
+    pub fn with_rate_limit(mut self, limit: RateLimit) -> Self {
+        self.policies.rate_limiter = Some(RateLimiter::new(limit));
+        self
+    }
+
+    /// Queues calls made by this client and the clones made after this by
+    /// `priority`, when they wait on a rate limit shared with other clones.
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.policies.priority = priority;
+        self
+    }
+
+    /// Sends requests as soon as they are made, e.g. when several processes
+    /// already share a limit of their own.
+    pub fn without_rate_limit(mut self) -> Self {
+        self.policies.rate_limiter = None;
+        self
+    }
+
+    /// Retries transient failures according to `policy`. Without one every
+    /// request is sent once.
+    pub fn with_retry(mut self, policy: RetryPolicy) -> Self {
+        self.policies.retry = Some(policy);
+        self
+    }
+
+    /// Answers calls from a cache as `policy` allows. Clones made after this
+    /// share the cache.
+    pub fn with_cache(mut self, policy: CachePolicy) -> Self {
+        self.policies.cache = Some(policy);
+        self
+    }
+
+    /// Stops sending calls for a while when Last.fm appears to be down, as
+    /// `policy` describes. The circuit is shared with the clones made after
+    /// this, not with earlier ones.
+    pub fn with_circuit_breaker(mut self, policy: CircuitBreakerPolicy) -> Self {
+        self.policies.circuit_breaker = Some(CircuitBreaker::new(policy));
+        self
+    }
+
+    /// Sends every call separately, even identical `GET` calls that are in
+    /// flight at the same time, which otherwise share one request.
+    pub fn without_request_coalescing(mut self) -> Self {
+        self.policies.coalescer = None;
+        self
+    }
+
     pub fn with_authentication<C: RequestComponent + 'static>(self, c: C) -> LastFm<C> {
         LastFm {
             client: self.client,
+            base_url: self.base_url,
+            policies: Policies {
+                // Calls made as someone else must not share responses.
+                coalescer: self.policies.coalescer.map(|_| Coalescer::default()),
+                ..self.policies
+            },
             authentication_component: c,
         }
     }
 
-    pub fn request(&mut self, http_method: Method, lastfm_method: &str) -> RequestBuilder {
-        let mut request = self
-            .client
-            .request(http_method, "https://ws.audioscrobbler.com/2.0/")
-            .query(&[("method", lastfm_method), ("format", "json")]);
-
-        request = self.authentication_component.apply(request);
-
-        request
+    pub fn request(&mut self, http_method: Method, lastfm_method: &str) -> LastFmRequest {
+        LastFmRequest::new(
+            self.client.clone(),
+            http_method,
+            self.base_url.clone(),
+            self.policies.clone(),
+            Arc::new(self.authentication_component.clone()),
+        )
+        .query(&[("method", lastfm_method), ("format", "json")])
     }
 }
@@ -0,0 +1,127 @@
+use chrono::{DateTime, Utc};
+use reqwest::Method;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::{
+    LastFm, RequestComponent,
+    authentication::{Enables, ReadPublic},
+    error::LastFmResult,
+    page::{Paginated, PaginationConfig},
+    request::LastFmRequest,
+};
+
+/// Parameters that are either added by the client itself or tracked
+/// separately by the cursor.
+const EXCLUDED_PARAMETERS: &[&str] = &["format", "api_key", "sk", "api_sig", "page", "limit"];
+
+/// A serialisable position within a paginated result.
+///
+/// Obtained from [`PaginatedStream::cursor`](super::paginated_stream::PaginatedStream::cursor)
+/// and turned back into a [`Paginated`] with [`LastFm::resume`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cursor {
+    pub method: String,
+    pub root: String,
+    pub content: String,
+    pub parameters: Vec<(String, String)>,
+    pub page_size: usize,
+    pub reverse: bool,
+    pub page: usize,
+    pub offset: usize,
+    pub window: TimeWindow,
+}
+
+/// The `from`/`to` range a query was made over.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct TimeWindow {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl Cursor {
+    pub(crate) fn new(
+        request: &LastFmRequest,
+        root: &str,
+        content: &str,
+        config: &PaginationConfig,
+        page: usize,
+        offset: usize,
+    ) -> Self {
+        let mut method = String::new();
+        let mut parameters = Vec::new();
+        let mut window = TimeWindow::default();
+
+        for (k, v) in request.parameters() {
+            match k.as_str() {
+                "method" => method = v.clone(),
+                "from" => window.from = parse_timestamp(v),
+                "to" => window.to = parse_timestamp(v),
+                k if EXCLUDED_PARAMETERS.contains(&k) => {}
+                _ => parameters.push((k.clone(), v.clone())),
+            }
+        }
+
+        Self {
+            method,
+            root: root.to_owned(),
+            content: content.to_owned(),
+            parameters,
+            page_size: config.page_size,
+            reverse: config.reverse,
+            page,
+            offset,
+            window,
+        }
+    }
+}
+
+fn parse_timestamp(v: &str) -> Option<DateTime<Utc>> {
+    v.parse().ok().and_then(|v| DateTime::from_timestamp(v, 0))
+}
+
+impl<T: RequestComponent + Enables<ReadPublic>> LastFm<T> {
+    /// Picks a paginated walk back up where `cursor` left off.
+    ///
+    /// `I` has to be the item type the cursor was taken from.
+    pub async fn resume<I: DeserializeOwned>(
+        &mut self,
+        cursor: &Cursor,
+    ) -> LastFmResult<Paginated<I>> {
+        self.resume_with(cursor, Default::default()).await
+    }
+
+    /// Like [`LastFm::resume`]. The page size and direction are always taken
+    /// from the cursor.
+    pub async fn resume_with<I: DeserializeOwned>(
+        &mut self,
+        cursor: &Cursor,
+        config: PaginationConfig,
+    ) -> LastFmResult<Paginated<I>> {
+        let mut request = self
+            .request(Method::GET, &cursor.method)
+            .query(&cursor.parameters);
+
+        if let Some(from) = cursor.window.from {
+            request = request.query(&[("from", from.timestamp())]);
+        }
+
+        if let Some(to) = cursor.window.to {
+            request = request.query(&[("to", to.timestamp())]);
+        }
+
+        let config = PaginationConfig {
+            page_size: cursor.page_size,
+            reverse: cursor.reverse,
+            ..config
+        };
+
+        Paginated::start(
+            request,
+            &cursor.root,
+            &cursor.content,
+            config,
+            Some((cursor.page, cursor.offset)),
+        )
+        .await
+    }
+}
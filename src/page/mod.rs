@@ -1,41 +1,116 @@
 pub mod attributes;
+pub mod cursor;
+pub mod paginated_stream;
+pub mod progress;
 pub mod serde;
+mod walk;
 
-use std::{marker::PhantomData, sync::Arc};
+use std::sync::Arc;
 
-use ::serde::de::{DeserializeOwned, DeserializeSeed};
-use futures::{Stream, stream};
-use reqwest::RequestBuilder;
-use ringbuffer::{AllocRingBuffer, RingBuffer};
+use ::serde::de::DeserializeOwned;
+use futures::{
+    Stream, StreamExt, TryStreamExt,
+    future::BoxFuture,
+    stream::{self, BoxStream, FuturesOrdered},
+};
 
 use crate::{
-    error::{LastFmResult, response::ResponseSeed},
-    page::{attributes::Attributes, serde::PageSeed},
+    error::{Error, LastFmResult},
+    page::{
+        attributes::Attributes,
+        cursor::Cursor,
+        paginated_stream::{PaginatedStream, Positioned},
+        progress::{ProgressHook, Tracker},
+        serde::PageSeed,
+        walk::Walk,
+    },
+    rate_limit::Priority,
+    request::LastFmRequest,
 };
 
+#[derive(Debug, Clone)]
 pub struct Page<T> {
     pub attr: Attributes,
     pub items: Vec<T>,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct PaginationConfig {
     pub page_size: usize,
+
+    /// How many pages to keep in flight once the page count is known. Items
+    /// are still yielded in page order.
+    pub prefetch: usize,
+
+    /// Walk the pages from last to first, reversing the items of each.
+    pub reverse: bool,
+
+    /// The page to start at instead of the first (or, when reversed, last).
+    pub start_page: Option<usize>,
+
+    /// The most pages to request, counting the ones fetched up front. A
+    /// reversed walk without a [`PaginationConfig::start_page`] fetches page 1
+    /// up front to find the last page, so it needs at least 2.
+    pub max_pages: Option<usize>,
+
+    /// The most items to yield. Pages that can only hold items past this are
+    /// never requested.
+    pub max_items: Option<usize>,
+
+    /// Told about every page the walk yields.
+    pub progress: Option<ProgressHook>,
+
+    /// Queues the page fetches by this rather than the client's priority. By
+    /// default they are [`Priority::Background`], so that interactive calls
+    /// go ahead of a long walk.
+    pub priority: Priority,
 }
 
 impl Default for PaginationConfig {
     fn default() -> Self {
-        PaginationConfig { page_size: 50 }
+        PaginationConfig {
+            page_size: 50,
+            prefetch: 1,
+            reverse: false,
+            start_page: None,
+            max_pages: None,
+            max_items: None,
+            progress: None,
+            priority: Priority::Background,
+        }
+    }
+}
+
+/// The largest `limit` a method accepts.
+fn max_page_size(method: &str) -> usize {
+    match method.to_ascii_lowercase().as_str() {
+        "user.getrecenttracks" => 200,
+        _ => 1000,
     }
 }
 
+/// A page along with where it sits in the walk.
+struct PageAt<T> {
+    number: usize,
+    /// How many leading items of the page were skipped.
+    offset: usize,
+    /// Whether trailing items of the page were cut off by
+    /// [`PaginationConfig::max_items`].
+    truncated: bool,
+    page: Page<T>,
+}
+
 pub struct Paginated<T: DeserializeOwned> {
-    request: RequestBuilder,
+    request: LastFmRequest,
     root: Arc<str>,
     content: Arc<str>,
-    phantom: PhantomData<fn() -> T>,
-    cache: Option<AllocRingBuffer<T>>,
-    attributes: Option<Attributes>,
+    first: Option<Page<T>>,
+    /// Page 1 of a reversed walk, fetched up front to find the last page and
+    /// kept until the walk gets back to it.
+    spare: Option<Page<T>>,
+    attributes: Attributes,
+    config: PaginationConfig,
+    cursor: Cursor,
 }
 
 pub trait PaginatedBuilder {
@@ -47,105 +122,365 @@ pub trait PaginatedBuilder {
     ) -> impl Future<Output = LastFmResult<Paginated<T>>>;
 }
 
-impl PaginatedBuilder for RequestBuilder {
+impl PaginatedBuilder for LastFmRequest {
     async fn paginated<T: DeserializeOwned>(
         self,
         root: &str,
         content: &str,
         config: PaginationConfig,
     ) -> LastFmResult<Paginated<T>> {
-        let mut pg = Paginated {
-            request: self.query(&[("limit", config.page_size)]),
-            root: Arc::from(root),
-            content: Arc::from(content),
-            phantom: PhantomData::default(),
-            cache: None,
-            attributes: None,
-        };
+        let position = config.start_page.map(|page| (page, 0));
 
-        pg.attributes = Some(pg.send_with(1).await?);
-
-        Ok(pg)
+        Paginated::start(self, root, content, config, position).await
     }
 }
 
+async fn fetch_page<T: DeserializeOwned>(
+    request: LastFmRequest,
+    root: Arc<str>,
+    content: Arc<str>,
+    page: usize,
+) -> LastFmResult<Page<T>> {
+    request
+        .query(&[("page", page)])
+        .fetch_seed(|| PageSeed::<T>::new(&root, &content))
+        .await
+}
+
 impl<T: DeserializeOwned> Paginated<T> {
-    async fn send_with(&mut self, page: usize) -> LastFmResult<Attributes> {
-        let bytes = self
-            .request
-            .try_clone()
-            .unwrap()
-            .query(&[("page", page)])
-            .send()
-            .await?
-            .bytes()
-            .await?;
-
-        let mut de = serde_json::Deserializer::from_slice(&bytes);
-
-        let page = ResponseSeed::new(PageSeed::<T>::new(&self.root, &self.content))
-            .deserialize(&mut de)?
-            .into_result()?;
-
-        if self.cache.as_ref().is_some_and(|c| c.len() != 0) {
-            panic!("Unexpected state");
+    /// Fetches the page at `position` and skips the items before it. Without
+    /// a position the walk starts at its first page, which for a reversed walk
+    /// is the last one.
+    pub(crate) async fn start(
+        request: LastFmRequest,
+        root: &str,
+        content: &str,
+        config: PaginationConfig,
+        position: Option<(usize, usize)>,
+    ) -> LastFmResult<Self> {
+        let request = request
+            .query(&[("limit", config.page_size)])
+            .with_priority(config.priority);
+
+        let (mut page, offset) = position.unwrap_or((1, 0));
+        // A reversed walk that ran to its end points before page 1. A forward
+        // one has nothing before it.
+        let finished = config.reverse && page == 0;
+        if !config.reverse {
+            page = page.max(1);
         }
+        let mut cursor = Cursor::new(&request, root, content, &config, page, offset);
 
-        if page.items.len() != 0 {
-            self.cache = Some(AllocRingBuffer::from(page.items));
-        } else {
-            self.cache = Some(AllocRingBuffer::new(1))
+        let max = max_page_size(&cursor.method);
+        if config.page_size == 0 || config.page_size > max {
+            return Err(Error::InvalidPageSize {
+                method: cursor.method,
+                page_size: config.page_size,
+                max,
+            });
+        }
+
+        let root = Arc::<str>::from(root);
+        let content = Arc::<str>::from(content);
+
+        let fetched =
+            fetch_page::<T>(request.clone(), root.clone(), content.clone(), page.max(1)).await?;
+        let mut attributes = fetched.attr;
+        let mut first = Some(fetched);
+        let mut spare = None;
+
+        if position.is_none() && config.reverse && attributes.total_pages > 1 {
+            page = attributes.total_pages as usize;
+            spare = first.take();
+
+            // Finding the last page may take the one request allowed, in which
+            // case the walk yields nothing.
+            if config.max_pages.is_none_or(|v| v > 1) {
+                let last =
+                    fetch_page::<T>(request.clone(), root.clone(), content.clone(), page).await?;
+                attributes = last.attr;
+                first = Some(last);
+            }
+        }
+
+        if finished {
+            // Only fetched for its attributes.
+            first = None;
+        }
+
+        if let Some(first) = &mut first {
+            if config.reverse {
+                first.items.reverse();
+            }
+
+            first.items.drain(..offset.min(first.items.len()));
         }
+        cursor.page = page;
+
+        Ok(Paginated {
+            request,
+            root,
+            content,
+            attributes,
+            first,
+            spare,
+            config,
+            cursor,
+        })
+    }
+
+    /// The position of the first item [`Paginated::send`] will yield.
+    pub fn cursor(&self) -> &Cursor {
+        &self.cursor
+    }
+
+    /// The attributes of the page fetched when this was created.
+    pub fn attributes(&self) -> &Attributes {
+        &self.attributes
+    }
+
+    pub fn total(&self) -> u32 {
+        self.attributes.total
+    }
+
+    pub fn total_pages(&self) -> u32 {
+        self.attributes.total_pages
+    }
+
+    /// The page that was fetched when this was created.
+    pub fn page(&self) -> u32 {
+        self.attributes.page
+    }
+}
+
+impl<T: DeserializeOwned + Send + 'static> Paginated<T> {
+    /// Streams every item, starting with the already fetched first page.
+    ///
+    /// Up to [`PaginationConfig::prefetch`] of the remaining pages are fetched
+    /// concurrently. Each goes through the same cache, retries, coalescing and
+    /// circuit breaker as a [`LastFmRequest::fetch`].
+    pub fn send(&mut self) -> PaginatedStream<T> {
+        let reverse = self.config.reverse;
+
+        let items = self
+            .page_stream()
+            .map_ok(move |v| {
+                stream::iter(
+                    positioned(v.number, v.offset, v.page.items, reverse, v.truncated).map(Ok),
+                )
+            })
+            .try_flatten();
+
+        PaginatedStream::new(items.boxed(), self.cursor.clone())
+    }
 
-        Ok(page.attr)
+    /// Like [`Paginated::send`], but yields whole pages along with their
+    /// [`Attributes`].
+    pub fn pages(&mut self) -> impl Stream<Item = LastFmResult<Page<T>>> + use<T> {
+        self.page_stream().map_ok(|v| v.page)
     }
 
-    pub fn send(&mut self) -> impl Stream<Item = LastFmResult<T>> + use<T> {
-        struct StreamHead<T: DeserializeOwned> {
-            pg: Paginated<T>,
-            page: usize,
+    fn page_stream(&mut self) -> BoxStream<'static, LastFmResult<PageAt<T>>> {
+        let first = self.first.take();
+        let spare = self.spare.take();
+        let (page, offset) = (self.cursor.page, self.cursor.offset);
+
+        let next = match (self.config.reverse, first.is_some()) {
+            (false, true) => page + 1,
+            (true, true) => page.saturating_sub(1),
+            (_, false) => page,
+        };
+
+        let mut walk = Walk::new(next, self.attributes.total_pages as usize, &self.config);
+
+        if let Some(first) = &first {
+            walk.skip_fetched(first.items.len());
+        }
+
+        if spare.is_some() {
+            walk.hold(1);
         }
 
-        let cache = self.cache.take();
-        let page = if cache.is_some() { 2 } else { 1 };
-
-        stream::try_unfold(
-            StreamHead {
-                page,
-                pg: Paginated {
-                    request: self
-                        .request
-                        .try_clone()
-                        .expect("To be able to clone the request"),
-                    root: self.root.clone(),
-                    content: self.content.clone(),
-                    phantom: self.phantom.clone(),
-                    cache: cache,
-                    attributes: self.attributes.clone(),
-                },
-            },
-            |mut st| async move {
-                if let Some(v) = st.pg.cache.as_mut().and_then(|v| v.dequeue()) {
-                    return Ok(Some((v, st)));
+        let walker = Walker {
+            tracker: Tracker::new(
+                self.config.progress.clone(),
+                first.is_some() as usize + spare.is_some() as usize,
+            ),
+            spare,
+            state: first.map_or(State::Walking, State::First),
+            walk,
+            in_flight: FuturesOrdered::new(),
+            request: self.request.clone(),
+            root: self.root.clone(),
+            content: self.content.clone(),
+            prefetch: self.config.prefetch,
+            reverse: self.config.reverse,
+            first_position: (page, offset),
+            budget: self.config.max_items,
+        };
+
+        stream::unfold(walker, |mut v| async move {
+            v.next().await.map(|page| (page, v))
+        })
+        .boxed()
+    }
+}
+
+enum State<T> {
+    /// The page fetched up front, which has not been yielded yet.
+    First(Page<T>),
+    Walking,
+    Done,
+}
+
+/// A page along with whether it was fetched just now.
+type InFlight<T> = BoxFuture<'static, (usize, LastFmResult<(Page<T>, bool)>)>;
+
+/// Drives a [`Walk`], keeping up to [`PaginationConfig::prefetch`] pages in
+/// flight and yielding them in order.
+struct Walker<T> {
+    state: State<T>,
+    walk: Walk,
+    in_flight: FuturesOrdered<InFlight<T>>,
+    /// Page 1, fetched up front, to be yielded when a reversed walk gets to it.
+    spare: Option<Page<T>>,
+    request: LastFmRequest,
+    root: Arc<str>,
+    content: Arc<str>,
+    prefetch: usize,
+    reverse: bool,
+    first_position: (usize, usize),
+    /// How many more items may be yielded.
+    budget: Option<usize>,
+    tracker: Tracker,
+}
+
+impl<T: DeserializeOwned + Send + 'static> Walker<T> {
+    async fn next(&mut self) -> Option<LastFmResult<PageAt<T>>> {
+        match std::mem::replace(&mut self.state, State::Walking) {
+            State::Done => {
+                self.state = State::Done;
+                None
+            }
+            State::First(page) => {
+                let (number, offset) = self.first_position;
+                Some(Ok(self.emit(number, offset, page)))
+            }
+            State::Walking => {
+                while self.in_flight.len() < self.prefetch.max(1)
+                    && let Some(page) = self.walk.next_page()
+                {
+                    if page == 1
+                        && let Some(spare) = self.spare.take()
+                    {
+                        self.in_flight
+                            .push_back(Box::pin(async move { (page, Ok((spare, false))) }));
+                        continue;
+                    }
+
+                    let fetch = fetch_page::<T>(
+                        self.request.clone(),
+                        self.root.clone(),
+                        self.content.clone(),
+                        page,
+                    );
+
+                    self.in_flight.push_back(Box::pin(async move {
+                        (page, fetch.await.map(|v| (v, true)))
+                    }));
                 }
 
-                let next_page = st.page;
-                st.page += 1;
+                let Some((number, result)) = self.in_flight.next().await else {
+                    self.finish();
+                    return None;
+                };
+
+                match result {
+                    Err(e) => {
+                        self.finish();
+                        Some(Err(e))
+                    }
+                    Ok((mut page, fetched)) => {
+                        if fetched {
+                            self.tracker.fetched();
+                        }
+
+                        self.walk.observe(&page.attr, page.items.len());
+
+                        if page.items.is_empty() {
+                            self.finish();
+                            return None;
+                        }
 
-                let v = st.pg.send_with(next_page).await?;
+                        if self.reverse {
+                            page.items.reverse();
+                        }
 
-                println!("{:?}", v);
-                if v.page > v.total_pages {
-                    return Ok(None);
+                        Some(Ok(self.emit(number, 0, page)))
+                    }
                 }
+            }
+        }
+    }
+
+    /// Cuts `page` down to what is left of the item budget.
+    fn emit(&mut self, number: usize, offset: usize, mut page: Page<T>) -> PageAt<T> {
+        let mut truncated = false;
+
+        if let Some(budget) = self.budget.as_mut() {
+            truncated = page.items.len() > *budget;
+            page.items.truncate(*budget);
+            *budget -= page.items.len();
+
+            if *budget == 0 {
+                self.finish();
+            }
+        }
 
-                Ok(st
-                    .pg
-                    .cache
-                    .as_mut()
-                    .and_then(|v| v.dequeue())
-                    .map(|v| (v, st)))
-            },
-        )
+        let pages_left = match self.state {
+            State::Done => 0,
+            _ => self.walk.remaining() + self.in_flight.len(),
+        };
+        self.tracker
+            .yielded(page.items.len(), &page.attr, pages_left);
+
+        PageAt {
+            number,
+            offset,
+            truncated,
+            page,
+        }
     }
+
+    fn finish(&mut self) {
+        self.state = State::Done;
+        self.in_flight = FuturesOrdered::new();
+    }
+}
+
+/// Pairs each item of `page` with the position of the item after it.
+fn positioned<T>(
+    page: usize,
+    offset: usize,
+    items: impl IntoIterator<Item = T, IntoIter: ExactSizeIterator>,
+    reverse: bool,
+    truncated: bool,
+) -> impl Iterator<Item = Positioned<T>> {
+    let items = items.into_iter();
+    let len = items.len();
+    let next_page = if reverse {
+        page.saturating_sub(1)
+    } else {
+        page + 1
+    };
+
+    items.enumerate().map(move |(i, item)| {
+        let (page, offset) = if i + 1 == len && !truncated {
+            (next_page, 0)
+        } else {
+            (page, offset + i + 1)
+        };
+
+        Positioned { item, page, offset }
+    })
 }
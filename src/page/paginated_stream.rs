@@ -0,0 +1,68 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll, ready},
+};
+
+use futures::{Stream, StreamExt, TryStreamExt, future, stream::BoxStream};
+
+use crate::{error::LastFmResult, page::cursor::Cursor};
+
+/// An item along with the position of the item after it.
+pub(crate) struct Positioned<T> {
+    pub item: T,
+    pub page: usize,
+    pub offset: usize,
+}
+
+/// The stream returned by [`Paginated::send`](super::Paginated::send).
+///
+/// Keeps track of how far it has got, so that an interrupted walk can be
+/// picked up again with [`LastFm::resume`](crate::LastFm::resume).
+pub struct PaginatedStream<T> {
+    inner: BoxStream<'static, LastFmResult<Positioned<T>>>,
+    cursor: Cursor,
+}
+
+impl<T: Send + 'static> PaginatedStream<T> {
+    pub(crate) fn new(
+        inner: BoxStream<'static, LastFmResult<Positioned<T>>>,
+        cursor: Cursor,
+    ) -> Self {
+        Self { inner, cursor }
+    }
+
+    /// The position of the next item this stream will yield.
+    pub fn cursor(&self) -> &Cursor {
+        &self.cursor
+    }
+
+    /// Drops items without losing track of the position.
+    pub(crate) fn filter_items(self, mut f: impl FnMut(&T) -> bool + Send + 'static) -> Self {
+        Self {
+            inner: self
+                .inner
+                .try_filter(move |v| future::ready(f(&v.item)))
+                .boxed(),
+            cursor: self.cursor,
+        }
+    }
+}
+
+impl<T> Stream for PaginatedStream<T> {
+    type Item = LastFmResult<T>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        Poll::Ready(match ready!(this.inner.poll_next_unpin(cx)) {
+            Some(Ok(v)) => {
+                this.cursor.page = v.page;
+                this.cursor.offset = v.offset;
+
+                Some(Ok(v.item))
+            }
+            Some(Err(e)) => Some(Err(e)),
+            None => None,
+        })
+    }
+}
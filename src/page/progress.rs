@@ -0,0 +1,96 @@
+use std::{
+    fmt,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use crate::page::attributes::Attributes;
+
+/// How far a paginated walk has got, as reported to a [`ProgressHook`].
+#[derive(Debug, Clone, Copy)]
+pub struct Progress {
+    /// Pages fetched so far, including the one fetched up front.
+    pub pages_fetched: usize,
+
+    /// Items handed out by the walk so far, before any filtering done on top
+    /// of it.
+    pub items_yielded: usize,
+
+    /// The total from the latest page, which can change mid-walk.
+    pub total_items: u32,
+
+    /// The page count from the latest page, which can change mid-walk.
+    pub total_pages: u32,
+
+    /// The time left at the pace of the pages fetched during the walk. Unknown
+    /// until one has been fetched.
+    pub eta: Option<Duration>,
+}
+
+/// Called with a [`Progress`] every time a paginated walk yields a page.
+#[derive(Clone)]
+pub struct ProgressHook(Arc<dyn Fn(Progress) + Send + Sync>);
+
+impl ProgressHook {
+    pub fn new(f: impl Fn(Progress) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+}
+
+impl fmt::Debug for ProgressHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressHook")
+    }
+}
+
+/// Keeps the counts behind the [`Progress`] of a single walk.
+pub(crate) struct Tracker {
+    hook: Option<ProgressHook>,
+    started: Instant,
+    /// Pages fetched before the walk started, which say nothing about its
+    /// pace.
+    fetched_up_front: usize,
+    pages_fetched: usize,
+    items_yielded: usize,
+}
+
+impl Tracker {
+    pub fn new(hook: Option<ProgressHook>, fetched_up_front: usize) -> Self {
+        Self {
+            hook,
+            started: Instant::now(),
+            fetched_up_front,
+            pages_fetched: fetched_up_front,
+            items_yielded: 0,
+        }
+    }
+
+    pub fn fetched(&mut self) {
+        self.pages_fetched += 1;
+    }
+
+    /// Counts `items` as yielded and reports, with `pages_left` still to be
+    /// fetched.
+    pub fn yielded(&mut self, items: usize, attr: &Attributes, pages_left: usize) {
+        self.items_yielded += items;
+
+        let Some(hook) = &self.hook else {
+            return;
+        };
+
+        let paced = self.pages_fetched - self.fetched_up_front;
+        let eta = match (pages_left, paced) {
+            (0, _) => Some(Duration::ZERO),
+            (_, 0) => None,
+            (left, paced) => Some(self.started.elapsed() / paced as u32 * left as u32),
+        };
+
+        (hook.0)(Progress {
+            pages_fetched: self.pages_fetched,
+            items_yielded: self.items_yielded,
+            total_items: attr.total,
+            total_pages: attr.total_pages,
+            eta,
+        });
+    }
+}
@@ -2,7 +2,10 @@ use std::{borrow::Cow, fmt, marker::PhantomData};
 
 use serde::{
     Deserialize, Deserializer,
-    de::{DeserializeSeed, MapAccess, Visitor},
+    de::{
+        DeserializeSeed, EnumAccess, IgnoredAny, MapAccess, SeqAccess, VariantAccess, Visitor,
+        value::{MapAccessDeserializer, StrDeserializer},
+    },
 };
 
 use crate::page::attributes::Attributes;
@@ -53,6 +56,7 @@ impl<'de, 'a, T: Deserialize<'de>> Visitor<'de> for TopVisitor<'a, T> {
             if key == self.root {
                 let inner = map.next_value_seed(self.content)?;
                 root_found = Some(inner);
+                drain(&mut map)?;
                 break;
             } else {
                 let _: serde::de::IgnoredAny = map.next_value()?;
@@ -63,6 +67,16 @@ impl<'de, 'a, T: Deserialize<'de>> Visitor<'de> for TopVisitor<'a, T> {
     }
 }
 
+/// Skips the rest of `map`. A streaming deserializer only accepts a map that
+/// was read to its end.
+fn drain<'de, A: MapAccess<'de>>(map: &mut A) -> Result<(), A::Error> {
+    while map.next_key::<IgnoredAny>()?.is_some() {
+        map.next_value::<IgnoredAny>()?;
+    }
+
+    Ok(())
+}
+
 impl<'de, 'a, T: Deserialize<'de>> DeserializeSeed<'de> for PageSeed<'a, T> {
     type Value = super::Page<T>;
 
@@ -74,15 +88,46 @@ impl<'de, 'a, T: Deserialize<'de>> DeserializeSeed<'de> for PageSeed<'a, T> {
     }
 }
 
-#[derive(Deserialize)]
-#[serde(untagged)]
-enum OneOrMany<T> {
+/// A list that Last.fm sends as a bare object when it has a single item.
+pub(crate) enum OneOrMany<T> {
     One(T),
     Many(Vec<T>),
 }
 
+/// Written out rather than `#[serde(untagged)]`, which buffers the value and
+/// so loses where in it an item failed.
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for OneOrMany<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(OneOrManyVisitor(PhantomData))
+    }
+}
+
+struct OneOrManyVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for OneOrManyVisitor<T> {
+    type Value = OneOrMany<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an item or a list of items")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut items = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+
+        Ok(OneOrMany::Many(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        T::deserialize(MapAccessDeserializer::new(map)).map(OneOrMany::One)
+    }
+}
+
 impl<T> OneOrMany<T> {
-    fn into_vec(self) -> Vec<T> {
+    pub(crate) fn into_vec(self) -> Vec<T> {
         match self {
             OneOrMany::Many(v) => v,
             OneOrMany::One(v) => vec![v],
@@ -101,18 +146,30 @@ impl<'de, 'a, T: Deserialize<'de>> Visitor<'de> for ContentVisitor<'a, T> {
         let mut attr_found = None;
         let mut items_found = None;
 
+        let (content, nested) = match self.content.split_once('.') {
+            Some((outer, inner)) => (outer, Some(inner)),
+            None => (self.content, None),
+        };
+
         while let Some(key) = map.next_key::<Cow<'de, str>>()? {
             if key == "@attr" {
                 let inner: Attributes = map.next_value()?;
                 attr_found = Some(inner);
-            } else if key == self.content {
-                let inner: OneOrMany<T> = map.next_value()?;
-                items_found = Some(inner.into_vec());
+            } else if key == content {
+                let inner = match nested {
+                    Some(nested) => map.next_value_seed(NestedSeed {
+                        content: nested,
+                        _m: PhantomData,
+                    })?,
+                    None => map.next_value::<OneOrMany<T>>()?.into_vec(),
+                };
+                items_found = Some(inner);
             } else {
                 let _: serde::de::IgnoredAny = map.next_value()?;
             }
 
             if attr_found.is_some() && items_found.is_some() {
+                drain(&mut map)?;
                 break;
             }
         }
@@ -134,3 +191,213 @@ impl<'de, 'a, T: Deserialize<'de>> DeserializeSeed<'de> for ContentSeed<'a, T> {
         })
     }
 }
+
+/// Items wrapped in one more object, e.g. `{ "artists": { "artist": [...] } }`.
+struct NestedSeed<'a, T> {
+    content: &'a str,
+    _m: PhantomData<T>,
+}
+
+impl<'de, 'a, T: Deserialize<'de>> Visitor<'de> for NestedSeed<'a, T> {
+    type Value = Vec<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a map like { content: [...] }")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut items_found = None;
+
+        while let Some(key) = map.next_key::<Cow<'de, str>>()? {
+            if key == self.content {
+                items_found = Some(map.next_value_seed(KindSeed {
+                    kind: self.content,
+                    _m: PhantomData,
+                })?);
+            } else {
+                let _: serde::de::IgnoredAny = map.next_value()?;
+            }
+        }
+
+        Ok(items_found.unwrap_or_default())
+    }
+}
+
+impl<'de, 'a, T: Deserialize<'de>> DeserializeSeed<'de> for NestedSeed<'a, T> {
+    type Value = Vec<T>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+/// One or many items under a key that names their kind, e.g. `artist` in
+/// `{ "artists": { "artist": [...] } }`.
+///
+/// An enum item is decoded as the variant named by the key rather than by
+/// guessing from its shape, so that a malformed item fails to decode instead of
+/// turning into another variant. Other items decode as usual.
+struct KindSeed<'a, T> {
+    kind: &'a str,
+    _m: PhantomData<T>,
+}
+
+impl<'de, 'a, T: Deserialize<'de>> DeserializeSeed<'de> for KindSeed<'a, T> {
+    type Value = Vec<T>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de, 'a, T: Deserialize<'de>> Visitor<'de> for KindSeed<'a, T> {
+    type Value = Vec<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a {} or a list of them", self.kind)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut items = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+
+        while let Some(item) = seq.next_element_seed(ItemSeed {
+            kind: self.kind,
+            _m: PhantomData,
+        })? {
+            items.push(item);
+        }
+
+        Ok(items)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        T::deserialize(Kind {
+            kind: self.kind,
+            inner: MapAccessDeserializer::new(map),
+        })
+        .map(|v| vec![v])
+    }
+}
+
+struct ItemSeed<'a, T> {
+    kind: &'a str,
+    _m: PhantomData<T>,
+}
+
+impl<'de, 'a, T: Deserialize<'de>> DeserializeSeed<'de> for ItemSeed<'a, T> {
+    type Value = T;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        T::deserialize(Kind {
+            kind: self.kind,
+            inner: deserializer,
+        })
+    }
+}
+
+/// Passes an item through to `inner`, presenting it as the `kind` variant
+/// when an enum is asked for.
+struct Kind<'a, D> {
+    kind: &'a str,
+    inner: D,
+}
+
+macro_rules! forward_to_inner {
+    ($($method:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, D::Error> {
+                self.inner.$method($($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, 'a, D: Deserializer<'de>> Deserializer<'de> for Kind<'a, D> {
+    type Error = D::Error;
+
+    forward_to_inner! {
+        deserialize_any();
+        deserialize_bool();
+        deserialize_i8();
+        deserialize_i16();
+        deserialize_i32();
+        deserialize_i64();
+        deserialize_i128();
+        deserialize_u8();
+        deserialize_u16();
+        deserialize_u32();
+        deserialize_u64();
+        deserialize_u128();
+        deserialize_f32();
+        deserialize_f64();
+        deserialize_char();
+        deserialize_str();
+        deserialize_string();
+        deserialize_bytes();
+        deserialize_byte_buf();
+        deserialize_option();
+        deserialize_unit();
+        deserialize_unit_struct(name: &'static str);
+        deserialize_newtype_struct(name: &'static str);
+        deserialize_seq();
+        deserialize_tuple(len: usize);
+        deserialize_tuple_struct(name: &'static str, len: usize);
+        deserialize_map();
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+        deserialize_identifier();
+        deserialize_ignored_any();
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, D::Error> {
+        visitor.visit_enum(self)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+impl<'de, 'a, D: Deserializer<'de>> EnumAccess<'de> for Kind<'a, D> {
+    type Error = D::Error;
+    type Variant = KindVariant<D>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), D::Error> {
+        let variant = seed.deserialize(StrDeserializer::new(self.kind))?;
+
+        Ok((variant, KindVariant(self.inner)))
+    }
+}
+
+struct KindVariant<D>(D);
+
+impl<'de, D: Deserializer<'de>> VariantAccess<'de> for KindVariant<D> {
+    type Error = D::Error;
+
+    fn unit_variant(self) -> Result<(), D::Error> {
+        IgnoredAny::deserialize(self.0).map(|_| ())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, D::Error> {
+        seed.deserialize(self.0)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, D::Error> {
+        self.0.deserialize_tuple(len, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, D::Error> {
+        self.0.deserialize_struct("", fields, visitor)
+    }
+}
@@ -0,0 +1,113 @@
+use crate::page::{PaginationConfig, attributes::Attributes};
+
+/// Decides which pages a paginated walk fetches, and when it is over.
+///
+/// Pages are handed out ahead of their responses so that several can be in
+/// flight. The walk ends at the last page known from the latest
+/// [`Attributes`], at the first empty page, or when a limit from
+/// [`PaginationConfig`] is reached, whichever comes first.
+#[derive(Debug, Clone)]
+pub(crate) struct Walk {
+    next: usize,
+    last: usize,
+    reverse: bool,
+    page_size: usize,
+    pages_left: Option<usize>,
+    items_unplanned: Option<usize>,
+    /// A page that was fetched before the walk began, out of order.
+    held: Option<usize>,
+    finished: bool,
+}
+
+impl Walk {
+    /// A walk that fetches `next` first, out of `last` pages.
+    pub fn new(next: usize, last: usize, config: &PaginationConfig) -> Self {
+        Self {
+            next,
+            last,
+            reverse: config.reverse,
+            page_size: config.page_size.max(1),
+            pages_left: config.max_pages,
+            items_unplanned: config.max_items,
+            held: None,
+            finished: false,
+        }
+    }
+
+    /// Counts a page holding `items` that was fetched before the walk began
+    /// against its limits.
+    pub fn skip_fetched(&mut self, items: usize) {
+        self.pages_left = self.pages_left.map(|v| v.saturating_sub(1));
+        self.items_unplanned = self.items_unplanned.map(|v| v.saturating_sub(items));
+    }
+
+    /// Takes note that `page` was fetched before the walk began, although the
+    /// walk only gets to it later. It counts against
+    /// [`PaginationConfig::max_pages`] now, as handing it out later sends no
+    /// request.
+    pub fn hold(&mut self, page: usize) {
+        self.held = Some(page);
+        self.pages_left = self.pages_left.map(|v| v.saturating_sub(1));
+    }
+
+    /// The next page to yield, if the walk and its limits allow another.
+    pub fn next_page(&mut self) -> Option<usize> {
+        let page = self.next;
+        let held = self.held == Some(page);
+
+        if self.finished || (self.pages_left == Some(0) && !held) || self.items_unplanned == Some(0)
+        {
+            return None;
+        }
+
+        if self.reverse {
+            if page == 0 {
+                return None;
+            }
+            self.next -= 1;
+        } else {
+            if page > self.last {
+                return None;
+            }
+            self.next += 1;
+        }
+
+        if !held {
+            self.pages_left = self.pages_left.map(|v| v - 1);
+        }
+        self.items_unplanned = self
+            .items_unplanned
+            .map(|v| v.saturating_sub(self.page_size));
+
+        Some(page)
+    }
+
+    /// How many more pages the walk expects to fetch, as far as it can tell.
+    pub fn remaining(&self) -> usize {
+        if self.finished {
+            return 0;
+        }
+
+        let pages = if self.reverse {
+            self.next
+        } else {
+            (self.last + 1).saturating_sub(self.next)
+        };
+
+        let pages = self.pages_left.map_or(pages, |v| pages.min(v));
+
+        self.items_unplanned
+            .map_or(pages, |v| pages.min(v.div_ceil(self.page_size)))
+    }
+
+    /// Takes in a fetched page, which may have moved the last page.
+    pub fn observe(&mut self, attr: &Attributes, items: usize) {
+        if items == 0 {
+            self.finished = true;
+        }
+
+        if !self.reverse {
+            self.last = attr.total_pages as usize;
+        }
+    }
+}
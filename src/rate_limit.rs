@@ -0,0 +1,159 @@
+use std::{
+    collections::BTreeSet,
+    pin::pin,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use tokio::sync::Notify;
+
+/// How many requests a client may send in a given period.
+///
+/// Up to `requests` can go out back to back, after which they are spaced out
+/// evenly over `per`.
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimit {
+    pub requests: u32,
+    pub per: Duration,
+}
+
+impl RateLimit {
+    pub fn per_second(requests: u32) -> Self {
+        Self {
+            requests,
+            per: Duration::from_secs(1),
+        }
+    }
+}
+
+/// The 5 requests a second Last.fm asks each API key to stay under.
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self::per_second(5)
+    }
+}
+
+/// Which calls go first when several are waiting on the [`RateLimit`].
+///
+/// A call waits for every call of a higher priority queued before or after it,
+/// and for calls of its own priority that were queued before it.
+///
+/// A call that joins an identical one already in flight shares its place in
+/// the queue, and so its priority. An interactive call for a page that a
+/// background walk is fetching waits as a background call.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    /// Someone is waiting for the answer.
+    #[default]
+    Interactive,
+
+    /// Bulk work such as exports, which only gets the budget interactive calls
+    /// leave over.
+    Background,
+}
+
+/// A token bucket shared by every clone of the client it was made for.
+#[derive(Clone)]
+pub(crate) struct RateLimiter(Arc<Shared>);
+
+struct Shared {
+    bucket: Mutex<Bucket>,
+    /// Wakes the waiting calls whenever one leaves the queue.
+    left: Notify,
+}
+
+struct Bucket {
+    capacity: f64,
+    /// Tokens gained per second.
+    rate: f64,
+    tokens: f64,
+    updated: Instant,
+    /// The calls waiting for a token, in the order they get one.
+    queue: BTreeSet<(Priority, u64)>,
+    next_ticket: u64,
+}
+
+impl Bucket {
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let gained = now.duration_since(self.updated).as_secs_f64() * self.rate;
+        self.tokens = (self.tokens + gained).min(self.capacity);
+        self.updated = now;
+    }
+}
+
+impl RateLimiter {
+    pub fn new(limit: RateLimit) -> Self {
+        let capacity = f64::from(limit.requests.max(1));
+
+        Self(Arc::new(Shared {
+            bucket: Mutex::new(Bucket {
+                capacity,
+                rate: capacity / limit.per.as_secs_f64().max(f64::EPSILON),
+                tokens: capacity,
+                updated: Instant::now(),
+                queue: BTreeSet::new(),
+                next_ticket: 0,
+            }),
+            left: Notify::new(),
+        }))
+    }
+
+    /// Waits for a token. Tokens are handed out by [`Priority`], and in the
+    /// order they were asked for within one.
+    pub async fn acquire(&self, priority: Priority) {
+        let ticket = {
+            let mut bucket = self.lock();
+            let ticket = (priority, bucket.next_ticket);
+            bucket.next_ticket += 1;
+            bucket.queue.insert(ticket);
+            ticket
+        };
+
+        // Leaves the queue even if the caller stops waiting.
+        let _queued = Queued(self, ticket);
+
+        loop {
+            // Listening before looking at the queue, so no call can leave
+            // unnoticed in between.
+            let mut left = pin!(self.0.left.notified());
+            left.as_mut().enable();
+
+            let wait = {
+                let mut bucket = self.lock();
+                bucket.refill();
+
+                let ahead = bucket.queue.range(..ticket).count() as f64;
+
+                if ahead == 0.0 && bucket.tokens >= 1.0 {
+                    bucket.tokens -= 1.0;
+                    return;
+                }
+
+                // Until there are tokens for the calls ahead as well, as long
+                // as no call of a higher priority turns up in the meantime.
+                // If there are already, those calls are about to leave.
+                let short = ahead + 1.0 - bucket.tokens;
+                (short > 0.0).then(|| Duration::from_secs_f64(short / bucket.rate))
+            };
+
+            match wait {
+                Some(wait) => _ = tokio::time::timeout(wait, left).await,
+                None => left.await,
+            }
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Bucket> {
+        self.0.bucket.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+struct Queued<'a>(&'a RateLimiter, (Priority, u64));
+
+impl Drop for Queued<'_> {
+    fn drop(&mut self) {
+        self.0.lock().queue.remove(&self.1);
+        self.0.0.left.notify_waiters();
+    }
+}
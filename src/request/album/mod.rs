@@ -0,0 +1,38 @@
+use reqwest::Method;
+
+use crate::{
+    LastFm, RequestComponent,
+    authentication::{Enables, ReadPublic},
+    error::LastFmResult,
+    request::{TagsResponse, TopTagsResponse, lookup::AlbumLookup},
+    types::tag::{Tag, TagWithCount},
+};
+
+impl<T: RequestComponent + Enables<ReadPublic>> LastFm<T> {
+    pub async fn album_get_top_tags<'a>(
+        &mut self,
+        album: impl Into<AlbumLookup<'a>>,
+    ) -> LastFmResult<Vec<TagWithCount>> {
+        album
+            .into()
+            .apply(self.request(Method::GET, "album.gettoptags"))
+            .fetch::<TopTagsResponse>()
+            .await
+            .map(TopTagsResponse::into_tags)
+    }
+
+    /// The tags `user` has applied to the album.
+    pub async fn album_get_tags<'a>(
+        &mut self,
+        album: impl Into<AlbumLookup<'a>>,
+        user: &str,
+    ) -> LastFmResult<Vec<Tag>> {
+        album
+            .into()
+            .apply(self.request(Method::GET, "album.gettags"))
+            .query(&[("user", user)])
+            .fetch::<TagsResponse>()
+            .await
+            .map(TagsResponse::into_tags)
+    }
+}
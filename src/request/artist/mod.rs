@@ -0,0 +1,38 @@
+use reqwest::Method;
+
+use crate::{
+    LastFm, RequestComponent,
+    authentication::{Enables, ReadPublic},
+    error::LastFmResult,
+    request::{TagsResponse, TopTagsResponse, lookup::ArtistLookup},
+    types::tag::{Tag, TagWithCount},
+};
+
+impl<T: RequestComponent + Enables<ReadPublic>> LastFm<T> {
+    pub async fn artist_get_top_tags<'a>(
+        &mut self,
+        artist: impl Into<ArtistLookup<'a>>,
+    ) -> LastFmResult<Vec<TagWithCount>> {
+        artist
+            .into()
+            .apply(self.request(Method::GET, "artist.gettoptags"))
+            .fetch::<TopTagsResponse>()
+            .await
+            .map(TopTagsResponse::into_tags)
+    }
+
+    /// The tags `user` has applied to the artist.
+    pub async fn artist_get_tags<'a>(
+        &mut self,
+        artist: impl Into<ArtistLookup<'a>>,
+        user: &str,
+    ) -> LastFmResult<Vec<Tag>> {
+        artist
+            .into()
+            .apply(self.request(Method::GET, "artist.gettags"))
+            .query(&[("user", user)])
+            .fetch::<TagsResponse>()
+            .await
+            .map(TagsResponse::into_tags)
+    }
+}
@@ -0,0 +1,84 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use bytes::Bytes;
+use futures::{
+    FutureExt,
+    future::{BoxFuture, WeakShared},
+};
+use reqwest::StatusCode;
+
+use crate::error::{Error, LastFmResult};
+
+/// What came back for a request, before any decoding.
+#[derive(Clone)]
+pub(crate) struct Exchange {
+    pub status: StatusCode,
+    pub body: Bytes,
+}
+
+type Flight = BoxFuture<'static, Result<Exchange, Arc<Error>>>;
+
+/// The flights under way, by the key of their request.
+type Flights = Arc<Mutex<HashMap<String, WeakShared<Flight>>>>;
+
+/// Lets identical requests that are in flight at the same time share one
+/// exchange.
+///
+/// Only the callers hold on to a flight, so it is dropped, and leaves any
+/// queue it waits in, once they have all given up on it.
+#[derive(Clone, Default)]
+pub(crate) struct Coalescer(Flights);
+
+impl Coalescer {
+    /// Waits for the flight under `key`, starting it with `exchange` if there
+    /// is none.
+    pub async fn run(
+        &self,
+        key: String,
+        exchange: impl Future<Output = LastFmResult<Exchange>> + Send + 'static,
+    ) -> LastFmResult<Exchange> {
+        let flight = {
+            let mut flights = self.0.lock().unwrap_or_else(|e| e.into_inner());
+
+            match flights.get(&key).and_then(WeakShared::upgrade) {
+                Some(flight) => flight,
+                None => {
+                    let landing = Landing(self.0.clone(), key.clone());
+
+                    let flight = async move {
+                        // Gone before anyone sees the result, so later callers
+                        // send a request of their own.
+                        let _landing = landing;
+                        exchange.await.map_err(Arc::new)
+                    }
+                    .boxed()
+                    .shared();
+
+                    if let Some(weak) = flight.downgrade() {
+                        flights.insert(key, weak);
+                    }
+                    flight
+                }
+            }
+        };
+
+        flight
+            .await
+            .map_err(|e| Arc::try_unwrap(e).unwrap_or_else(Error::Shared))
+    }
+}
+
+/// Takes a flight out of the map when it ends or is dropped.
+struct Landing(Flights, String);
+
+impl Drop for Landing {
+    fn drop(&mut self) {
+        self.0
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&self.1);
+    }
+}
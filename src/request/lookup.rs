@@ -0,0 +1,67 @@
+use crate::request::LastFmRequest;
+
+/// How an artist is identified: by name, or by MusicBrainz ID.
+#[derive(Debug, Clone, Copy)]
+pub enum ArtistLookup<'a> {
+    Name(&'a str),
+    MusicBrainzId(&'a str),
+}
+
+/// How an album is identified: by artist and title, or by MusicBrainz ID.
+#[derive(Debug, Clone, Copy)]
+pub enum AlbumLookup<'a> {
+    Name { artist: &'a str, album: &'a str },
+    MusicBrainzId(&'a str),
+}
+
+/// How a track is identified: by artist and title, or by MusicBrainz ID.
+#[derive(Debug, Clone, Copy)]
+pub enum TrackLookup<'a> {
+    Name { artist: &'a str, track: &'a str },
+    MusicBrainzId(&'a str),
+}
+
+impl ArtistLookup<'_> {
+    pub(crate) fn apply(&self, req: LastFmRequest) -> LastFmRequest {
+        match self {
+            Self::Name(artist) => req.query(&[("artist", artist)]),
+            Self::MusicBrainzId(mbid) => req.query(&[("mbid", mbid)]),
+        }
+    }
+}
+
+impl AlbumLookup<'_> {
+    pub(crate) fn apply(&self, req: LastFmRequest) -> LastFmRequest {
+        match self {
+            Self::Name { artist, album } => req.query(&[("artist", artist), ("album", album)]),
+            Self::MusicBrainzId(mbid) => req.query(&[("mbid", mbid)]),
+        }
+    }
+}
+
+impl TrackLookup<'_> {
+    pub(crate) fn apply(&self, req: LastFmRequest) -> LastFmRequest {
+        match self {
+            Self::Name { artist, track } => req.query(&[("artist", artist), ("track", track)]),
+            Self::MusicBrainzId(mbid) => req.query(&[("mbid", mbid)]),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for ArtistLookup<'a> {
+    fn from(artist: &'a str) -> Self {
+        Self::Name(artist)
+    }
+}
+
+impl<'a> From<(&'a str, &'a str)> for AlbumLookup<'a> {
+    fn from((artist, album): (&'a str, &'a str)) -> Self {
+        Self::Name { artist, album }
+    }
+}
+
+impl<'a> From<(&'a str, &'a str)> for TrackLookup<'a> {
+    fn from((artist, track): (&'a str, &'a str)) -> Self {
+        Self::Name { artist, track }
+    }
+}
@@ -1 +1,378 @@
+pub mod album;
+pub mod artist;
+pub(crate) mod coalesce;
+pub mod lookup;
+pub mod track;
 pub mod user;
+
+use std::{marker::PhantomData, sync::Arc};
+
+use bytes::Bytes;
+use reqwest::{Method, RequestBuilder, StatusCode};
+use serde::{
+    Deserialize,
+    de::{DeserializeOwned, DeserializeSeed},
+};
+
+use crate::{
+    RequestComponent,
+    cache::CachePolicy,
+    circuit_breaker::CircuitBreaker,
+    error::{Error, LastFmResult, context::RequestContext, last_fm::LastFmError, response},
+    page::serde::OneOrMany,
+    rate_limit::{Priority, RateLimiter},
+    request::coalesce::{Coalescer, Exchange},
+    retry::RetryPolicy,
+    types::tag::{Tag, TagWithCount},
+};
+
+/// Object-safe view of a [`RequestComponent`].
+pub(crate) trait Component: Send + Sync {
+    fn apply(&self, req: RequestBuilder) -> RequestBuilder;
+    fn sign(&self, req: &mut reqwest::Request);
+    fn identity(&self) -> Option<String>;
+}
+
+impl<C: RequestComponent> Component for C {
+    fn apply(&self, req: RequestBuilder) -> RequestBuilder {
+        RequestComponent::apply(self, req)
+    }
+
+    fn sign(&self, req: &mut reqwest::Request) {
+        RequestComponent::sign(self, req)
+    }
+
+    fn identity(&self) -> Option<String> {
+        RequestComponent::identity(self)
+    }
+}
+
+/// How calls made by a client are sent, passed on to each of its requests.
+#[derive(Clone, Default)]
+pub(crate) struct Policies {
+    pub rate_limiter: Option<RateLimiter>,
+    pub retry: Option<RetryPolicy>,
+    pub cache: Option<CachePolicy>,
+    pub coalescer: Option<Coalescer>,
+    pub circuit_breaker: Option<CircuitBreaker>,
+    pub priority: Priority,
+}
+
+/// A Last.fm API call under construction.
+///
+/// Holds the parameters of the call rather than a [`RequestBuilder`], so that
+/// it can always be cloned, and so that the authentication component gets to
+/// sign the final parameter set when the request is sent.
+#[derive(Clone)]
+pub struct LastFmRequest {
+    client: reqwest::Client,
+    http_method: Method,
+    url: Arc<str>,
+    parameters: Vec<(String, String)>,
+    policies: Policies,
+    component: Arc<dyn Component>,
+}
+
+impl LastFmRequest {
+    pub(crate) fn new(
+        client: reqwest::Client,
+        http_method: Method,
+        url: Arc<str>,
+        policies: Policies,
+        component: Arc<dyn Component>,
+    ) -> Self {
+        Self {
+            client,
+            http_method,
+            url,
+            parameters: Vec::new(),
+            policies,
+            component,
+        }
+    }
+
+    pub fn query<K: AsRef<str>, V: ToString>(mut self, pairs: &[(K, V)]) -> Self {
+        self.parameters.extend(
+            pairs
+                .iter()
+                .map(|(k, v)| (k.as_ref().to_owned(), v.to_string())),
+        );
+        self
+    }
+
+    /// Queues the call behind the others waiting on the rate limit by
+    /// `priority` rather than the client's.
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.policies.priority = priority;
+        self
+    }
+
+    /// Every parameter of the call, in the order they were added. This does
+    /// not include the ones added by the authentication component.
+    pub fn parameters(&self) -> &[(String, String)] {
+        &self.parameters
+    }
+
+    /// The parameters that make up the call, in a canonical order, as used to
+    /// tell calls apart. Leaves out the API key and signature, and stands in
+    /// the [`RequestComponent::identity`] for the session key.
+    pub fn canonical_key(&self) -> String {
+        let identity = self.component.identity();
+
+        let mut parameters: Vec<_> = self
+            .parameters
+            .iter()
+            .filter(|(k, _)| !KEY_IGNORED_PARAMETERS.contains(&k.as_str()))
+            .map(|(k, v)| (k.to_ascii_lowercase(), v.as_str()))
+            .chain(identity.as_deref().map(|v| ("sk".to_owned(), v)))
+            .collect();
+
+        parameters.sort();
+
+        parameters
+            .iter()
+            .map(|(k, v)| format!("{}={}", escape(k), escape(v)))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+
+    /// Sends the request once, without looking at the response.
+    pub async fn send(self) -> LastFmResult<reqwest::Response> {
+        let context = self.context(None);
+        let breaker = self.policies.circuit_breaker.clone();
+
+        if let Some(breaker) = &breaker {
+            breaker
+                .admit()
+                .map_err(|e| e.with_context(context.clone()))?;
+        }
+
+        let result = self.dispatch().await;
+
+        if let Some(breaker) = &breaker {
+            breaker.record(result.as_ref().is_err_and(Error::is_outage));
+        }
+
+        result.map_err(|e| e.with_context(context))
+    }
+
+    async fn dispatch(self) -> LastFmResult<reqwest::Response> {
+        let builder = self
+            .client
+            .request(self.http_method, &*self.url)
+            .query(&self.parameters);
+
+        let (client, request) = self.component.apply(builder).build_split();
+        let mut request = request.map_err(reqwest::Error::without_url)?;
+
+        self.component.sign(&mut request);
+
+        if let Some(limiter) = &self.policies.rate_limiter {
+            limiter.acquire(self.policies.priority).await;
+        }
+
+        // The URL holds the API key and session, so it is kept out of errors.
+        Ok(client
+            .execute(request)
+            .await
+            .map_err(reqwest::Error::without_url)?)
+    }
+
+    /// Sends the request and reads the body, sharing the exchange with
+    /// identical `GET` calls in flight at the same time.
+    async fn exchange(&self) -> LastFmResult<Exchange> {
+        let request = self.clone();
+        let breaker = self.policies.circuit_breaker.clone();
+
+        let exchange = async move {
+            let result: LastFmResult<Exchange> = async {
+                let response = request.dispatch().await?;
+                let status = response.status();
+                let body = response
+                    .bytes()
+                    .await
+                    .map_err(reqwest::Error::without_url)?;
+
+                Ok(Exchange { status, body })
+            }
+            .await;
+
+            // Inside the flight, so callers that share it count once.
+            if let Some(breaker) = &breaker {
+                breaker.record(match &result {
+                    Ok(exchange) => {
+                        response::api_error(&exchange.body).is_some_and(|e| e.is_outage())
+                    }
+                    Err(e) => e.is_outage(),
+                });
+            }
+
+            result
+        };
+
+        match &self.policies.coalescer {
+            Some(coalescer) if self.http_method == Method::GET => {
+                coalescer.run(self.canonical_key(), exchange).await
+            }
+            _ => exchange.await,
+        }
+    }
+
+    /// Sends the request and decodes the response, retrying transient failures
+    /// if the client has a [`RetryPolicy`] and caching it if it has a
+    /// [`CachePolicy`].
+    pub async fn fetch<T: DeserializeOwned>(self) -> LastFmResult<T> {
+        self.fetch_seed(PhantomData::<T>::default).await
+    }
+
+    /// Like [`LastFmRequest::fetch`], decoding with a fresh `seed()` on every
+    /// attempt.
+    ///
+    /// `GET` calls the client's [`CachePolicy`] covers are answered from the
+    /// cache while fresh, and from a stale entry when Last.fm is offline or
+    /// the circuit breaker is open.
+    pub(crate) async fn fetch_seed<S, T>(self, seed: impl Fn() -> S) -> LastFmResult<T>
+    where
+        S: for<'de> DeserializeSeed<'de, Value = T>,
+    {
+        let lookup = match &self.policies.cache {
+            Some(cache) if self.http_method == Method::GET => cache.lookup(&self),
+            _ => None,
+        };
+
+        let Some(lookup) = lookup else {
+            return self.fetch_retrying(&seed).await.map(|(v, _)| v);
+        };
+
+        if let Some(v) = lookup.fresh().and_then(|body| decode_cached(body, seed())) {
+            return Ok(v);
+        }
+
+        match self.fetch_retrying(&seed).await {
+            Ok((v, body)) => {
+                lookup.store(body);
+                Ok(v)
+            }
+            Err(e) => {
+                let offline = matches!(
+                    e.inner(),
+                    Error::ApiError(LastFmError::ServiceOffline { .. }) | Error::CircuitOpen { .. }
+                );
+
+                lookup
+                    .stale()
+                    .filter(|_| offline)
+                    .and_then(|body| decode_cached(body, seed()))
+                    .ok_or(e)
+            }
+        }
+    }
+
+    /// Fetches the call, retrying transient failures if the client has a
+    /// [`RetryPolicy`]. Returns the body along with what it decoded to.
+    async fn fetch_retrying<S, T>(&self, seed: &impl Fn() -> S) -> LastFmResult<(T, Bytes)>
+    where
+        S: for<'de> DeserializeSeed<'de, Value = T>,
+    {
+        let idempotent = self.http_method.is_idempotent();
+        let mut attempt = 0;
+
+        loop {
+            let result = self.clone().fetch_once(seed()).await;
+
+            let backoff = match (&result, self.policies.retry) {
+                (Err(e), Some(policy)) if policy.should_retry(e, attempt, idempotent) => {
+                    policy.backoff(attempt)
+                }
+                _ => return result,
+            };
+
+            tokio::time::sleep(backoff).await;
+            attempt += 1;
+        }
+    }
+
+    async fn fetch_once<S, T>(self, seed: S) -> LastFmResult<(T, Bytes)>
+    where
+        S: for<'de> DeserializeSeed<'de, Value = T>,
+    {
+        if let Some(breaker) = &self.policies.circuit_breaker {
+            breaker
+                .admit()
+                .map_err(|e| e.with_context(self.context(None)))?;
+        }
+
+        let mut status = None;
+
+        let result = async {
+            let Exchange { status: code, body } = self.exchange().await?;
+            status = Some(code);
+
+            match response::decode(&body, seed) {
+                Ok(v) => v.into_result().map(|v| (v, body)),
+                // A body that is not an API error says less than the status.
+                Err(_) if code.is_client_error() || code.is_server_error() => {
+                    Err(Error::HttpStatus(code))
+                }
+                Err(e) => Err(e.with_status(code).into()),
+            }
+        }
+        .await;
+
+        result.map_err(|e| e.with_context(self.context(status)))
+    }
+
+    fn context(&self, status: Option<StatusCode>) -> RequestContext {
+        RequestContext::new(&self.parameters, status)
+    }
+}
+
+/// Parameters left out of [`LastFmRequest::canonical_key`]. Most say nothing
+/// about the response, and the session key is stood in for by its identity.
+const KEY_IGNORED_PARAMETERS: &[&str] = &["api_key", "api_sig", "sk", "format", "callback"];
+
+fn escape(v: &str) -> String {
+    v.replace('%', "%25")
+        .replace('&', "%26")
+        .replace('=', "%3D")
+}
+
+/// Decodes a cached body, which is only used if it is a success.
+fn decode_cached<S, T>(body: &Bytes, seed: S) -> Option<T>
+where
+    S: for<'de> DeserializeSeed<'de, Value = T>,
+{
+    response::decode(body, seed).ok()?.into_result().ok()
+}
+
+#[derive(Deserialize)]
+pub(crate) struct TopTagsResponse {
+    toptags: TagList<TagWithCount>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct TagsResponse {
+    tags: TagList<Tag>,
+}
+
+/// Last.fm leaves out `tag` entirely when there are none.
+#[derive(Deserialize)]
+struct TagList<T> {
+    #[serde(default = "Option::default")]
+    tag: Option<OneOrMany<T>>,
+}
+
+impl TopTagsResponse {
+    pub(crate) fn into_tags(self) -> Vec<TagWithCount> {
+        self.toptags
+            .tag
+            .map(OneOrMany::into_vec)
+            .unwrap_or_default()
+    }
+}
+
+impl TagsResponse {
+    pub(crate) fn into_tags(self) -> Vec<Tag> {
+        self.tags.tag.map(OneOrMany::into_vec).unwrap_or_default()
+    }
+}
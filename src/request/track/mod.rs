@@ -0,0 +1,38 @@
+use reqwest::Method;
+
+use crate::{
+    LastFm, RequestComponent,
+    authentication::{Enables, ReadPublic},
+    error::LastFmResult,
+    request::{TagsResponse, TopTagsResponse, lookup::TrackLookup},
+    types::tag::{Tag, TagWithCount},
+};
+
+impl<T: RequestComponent + Enables<ReadPublic>> LastFm<T> {
+    pub async fn track_get_top_tags<'a>(
+        &mut self,
+        track: impl Into<TrackLookup<'a>>,
+    ) -> LastFmResult<Vec<TagWithCount>> {
+        track
+            .into()
+            .apply(self.request(Method::GET, "track.gettoptags"))
+            .fetch::<TopTagsResponse>()
+            .await
+            .map(TopTagsResponse::into_tags)
+    }
+
+    /// The tags `user` has applied to the track.
+    pub async fn track_get_tags<'a>(
+        &mut self,
+        track: impl Into<TrackLookup<'a>>,
+        user: &str,
+    ) -> LastFmResult<Vec<Tag>> {
+        track
+            .into()
+            .apply(self.request(Method::GET, "track.gettags"))
+            .query(&[("user", user)])
+            .fetch::<TagsResponse>()
+            .await
+            .map(TagsResponse::into_tags)
+    }
+}
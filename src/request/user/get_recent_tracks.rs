@@ -1,13 +1,16 @@
-use chrono::{DateTime, Utc};
-use futures::{Stream, StreamExt};
-use reqwest::{Method, RequestBuilder};
+use std::collections::{HashSet, VecDeque};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use futures::{Stream, StreamExt, TryStreamExt, future, stream};
+use reqwest::Method;
 
 use crate::{
     LastFm, RequestComponent,
     authentication::{Enables, ReadPublic},
     error::LastFmResult,
-    page::{PaginatedBuilder, PaginationConfig},
-    types::track::Track,
+    page::{PaginatedBuilder, PaginationConfig, paginated_stream::PaginatedStream},
+    request::LastFmRequest,
+    types::track::{BasicTrack, RecentTrack, Track},
 };
 
 pub struct GetRecentTracks<'a> {
@@ -16,7 +19,8 @@ pub struct GetRecentTracks<'a> {
     to: Option<DateTime<Utc>>,
     config: PaginationConfig,
     include_now_playing: bool,
-    request: RequestBuilder,
+    oldest_first: bool,
+    request: LastFmRequest,
 }
 
 impl<T: RequestComponent + Enables<ReadPublic>> LastFm<T> {
@@ -27,6 +31,7 @@ impl<T: RequestComponent + Enables<ReadPublic>> LastFm<T> {
             to: Default::default(),
             config: Default::default(),
             include_now_playing: Default::default(),
+            oldest_first: Default::default(),
             request: self.request(Method::GET, "user.getrecenttracks"),
         }
     }
@@ -53,37 +58,269 @@ impl<'a> GetRecentTracks<'a> {
         self
     }
 
-    pub async fn fetch(self) -> LastFmResult<impl Stream<Item = LastFmResult<Track>>> {
-        let mut request = self
-            .request
-            .query(&[("user", self.user), ("extended", "1")]);
+    /// Yields history in chronological order by walking the pages from last
+    /// to first.
+    pub fn oldest_first(mut self) -> Self {
+        self.oldest_first = true;
+        self
+    }
+
+    pub(crate) fn with_oldest_first(mut self, oldest_first: bool) -> Self {
+        self.oldest_first = oldest_first;
+        self
+    }
+
+    pub async fn fetch(self) -> LastFmResult<PaginatedStream<Track>> {
+        self.fetch_as::<Track>().await
+    }
+
+    /// Fetches the non-extended shape, which is smaller but carries no loved
+    /// status or artist images.
+    pub async fn fetch_basic(self) -> LastFmResult<PaginatedStream<BasicTrack>> {
+        self.fetch_as::<BasicTrack>().await
+    }
+
+    /// Splits the date range into windows of `window` and walks up to
+    /// `concurrency` of them at once, which also keeps page numbers low on
+    /// huge histories.
+    ///
+    /// Items are still yielded in order, but each window is buffered in full
+    /// before it is yielded. Without a start date the range starts at the
+    /// user's oldest scrobble, which takes a request for the last page of the
+    /// whole history. A [`PaginationConfig::progress`] hook is told about each
+    /// window's walk on its own.
+    ///
+    /// [`PaginationConfig::max_items`] limits the merged stream. Each window
+    /// is walked in full from its first page, so
+    /// [`PaginationConfig::start_page`] and [`PaginationConfig::max_pages`]
+    /// are ignored.
+    pub async fn fetch_sharded<I: RecentTrack>(
+        self,
+        window: TimeDelta,
+        concurrency: usize,
+    ) -> LastFmResult<impl Stream<Item = LastFmResult<I>>> {
+        let template = self.template::<I>();
+        let to = self.anchor::<I>(&template).await?;
+
+        let from = match self.from {
+            Some(from) => Some(from),
+            None => oldest_scrobble::<I>(&template, to).await?,
+        };
+
+        let Some(from) = from else {
+            return Ok(stream::empty().left_stream());
+        };
+
+        let mut windows = Vec::new();
+        let mut start = from;
+        while start < to {
+            let end = (start + window.max(TimeDelta::seconds(1))).min(to);
+            windows.push((start, end));
+            start = end;
+        }
+
+        if !self.oldest_first {
+            windows.reverse();
+        }
+
+        let config = PaginationConfig {
+            reverse: self.oldest_first,
+            start_page: None,
+            max_pages: None,
+            ..self.config.clone()
+        };
+        let max_items = config.max_items.unwrap_or(usize::MAX);
+
+        let items = stream::iter(windows)
+            .map(move |(from, to)| {
+                // Windows overlap by a second so that nothing on a boundary is
+                // lost, whether Last.fm treats the range as inclusive or not.
+                let request = template
+                    .clone()
+                    .query(&[("from", from.timestamp() - 1), ("to", to.timestamp())]);
+                let config = config.clone();
+
+                async move {
+                    request
+                        .paginated::<I>("recenttracks", "track", config)
+                        .await?
+                        .send()
+                        .try_collect::<Vec<_>>()
+                        .await
+                }
+            })
+            .buffered(concurrency.max(1))
+            .map_ok(|v| stream::iter(v.into_iter().map(Ok)))
+            .try_flatten();
+
+        let mut filter = self.filter();
+
+        Ok(items
+            .try_filter(move |v| future::ready(filter(v)))
+            .take(max_items)
+            .right_stream())
+    }
+
+    async fn fetch_as<I: RecentTrack>(self) -> LastFmResult<PaginatedStream<I>> {
+        let mut request = self.template::<I>();
+        let to = self.anchor::<I>(&request).await?;
 
         if let Some(from) = self.from {
             request = request.query(&[("from", from.timestamp())]);
         }
 
+        request = request.query(&[("to", to.timestamp())]);
+
+        let config = PaginationConfig {
+            reverse: self.oldest_first,
+            ..self.config.clone()
+        };
+
+        let mut v = request
+            .paginated::<I>("recenttracks", "track", config)
+            .await?;
+
+        Ok(v.send().filter_items(self.filter()))
+    }
+
+    /// The end of the range to walk: the end date if there is one, or else
+    /// just past the newest scrobble on the first page.
+    ///
+    /// Pinning `to` keeps scrobbles submitted mid-walk from shifting the pages
+    /// underneath it. Pinning it to the newest scrobble rather than to now also
+    /// keeps out those that are submitted late, and so are timestamped before
+    /// now.
+    async fn anchor<I: RecentTrack>(
+        &self,
+        template: &LastFmRequest,
+    ) -> LastFmResult<DateTime<Utc>> {
         if let Some(to) = self.to {
-            request = request.query(&[("to", to.timestamp())]);
+            return Ok(to);
         }
 
+        let newest = newest_scrobble::<I>(template).await?;
+
+        Ok(newest.map_or_else(Utc::now, |v| v + TimeDelta::seconds(1)))
+    }
+
+    /// The request without a date range.
+    fn template<I: RecentTrack>(&self) -> LastFmRequest {
+        self.request.clone().query(&[
+            ("user", self.user),
+            ("extended", if I::EXTENDED { "1" } else { "0" }),
+        ])
+    }
+
+    /// Drops the now playing track unless asked for, and any scrobble that
+    /// has already been yielded.
+    fn filter<I: RecentTrack>(&self) -> impl FnMut(&I) -> bool + Send + use<I> {
         let mut should_emit_now_playing = self.include_now_playing;
+        let mut seen = RecentlySeen::new(self.config.page_size);
 
-        let mut v = request
-            .paginated::<Track>("recenttracks", "track", self.config)
-            .await?;
+        move |v| {
+            if v.now_playing() {
+                let cap = should_emit_now_playing;
+                should_emit_now_playing = false;
+                cap
+            } else {
+                seen.insert(v)
+            }
+        }
+    }
+}
 
-        Ok(v.send().filter(move |v| {
-            let x = match v.as_ref() {
-                Err(_) => true,
-                Ok(v) if v.now_playing => {
-                    let cap = should_emit_now_playing;
-                    should_emit_now_playing = false;
-                    cap
-                }
-                Ok(_) => true,
-            };
+/// The time of the newest scrobble, from a one-item first page.
+async fn newest_scrobble<I: RecentTrack>(
+    template: &LastFmRequest,
+) -> LastFmResult<Option<DateTime<Utc>>> {
+    let config = PaginationConfig {
+        page_size: 1,
+        max_pages: Some(1),
+        ..Default::default()
+    };
+
+    let newest = template
+        .clone()
+        .paginated::<I>("recenttracks", "track", config)
+        .await?
+        .send()
+        .try_filter(|v| future::ready(!v.now_playing()))
+        .try_next()
+        .await?;
+
+    Ok(newest.and_then(|v| v.played_at()))
+}
+
+/// The time of the oldest scrobble before `to`, from the last page.
+///
+/// Pages are as large as Last.fm allows to keep the last one shallow, but on a
+/// history of 200,000 scrobbles it is still page 1,000, which Last.fm is slow
+/// to serve. Pass a start date to skip this.
+async fn oldest_scrobble<I: RecentTrack>(
+    template: &LastFmRequest,
+    to: DateTime<Utc>,
+) -> LastFmResult<Option<DateTime<Utc>>> {
+    let config = PaginationConfig {
+        page_size: 200,
+        reverse: true,
+        max_pages: Some(2),
+        max_items: Some(1),
+        ..Default::default()
+    };
+
+    let oldest = template
+        .clone()
+        .query(&[("to", to.timestamp())])
+        .paginated::<I>("recenttracks", "track", config)
+        .await?
+        .send()
+        .try_next()
+        .await?;
+
+    Ok(oldest.and_then(|v| v.played_at()))
+}
+
+/// Remembers the last page worth of scrobbles, so that one pushed across a
+/// page boundary (e.g. by a backdated scrobble) is only yielded once.
+struct RecentlySeen {
+    keys: HashSet<(i64, String, String)>,
+    order: VecDeque<(i64, String, String)>,
+    capacity: usize,
+}
+
+impl RecentlySeen {
+    fn new(capacity: usize) -> Self {
+        Self {
+            keys: HashSet::with_capacity(capacity),
+            order: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Returns whether `track` had not been seen yet.
+    fn insert(&mut self, track: &impl RecentTrack) -> bool {
+        let Some(played_at) = track.played_at() else {
+            return true;
+        };
+
+        let key = (
+            played_at.timestamp(),
+            track.artist_name().to_owned(),
+            track.name().to_owned(),
+        );
+
+        if !self.keys.insert(key.clone()) {
+            return false;
+        }
+
+        if self.order.len() == self.capacity
+            && let Some(oldest) = self.order.pop_front()
+        {
+            self.keys.remove(&oldest);
+        }
+
+        self.order.push_back(key);
 
-            async move { x }
-        }))
+        true
     }
 }
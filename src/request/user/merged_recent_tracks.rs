@@ -0,0 +1,157 @@
+use std::sync::Arc;
+
+use futures::{
+    Stream, StreamExt,
+    future::{self, try_join_all},
+    stream::{self, BoxStream},
+};
+
+use crate::{
+    LastFm, RequestComponent,
+    authentication::{Enables, ReadPublic},
+    error::LastFmResult,
+    request::user::get_recent_tracks::GetRecentTracks,
+    types::track::{RecentTrack, Track},
+};
+
+/// A scrobble along with the user it belongs to.
+#[derive(Debug, Clone)]
+pub struct UserTrack<I = Track> {
+    pub user: Arc<str>,
+    pub track: I,
+}
+
+impl<T: RequestComponent + Enables<ReadPublic>> LastFm<T> {
+    /// Walks the recent tracks of every user in `users` at once, merged into
+    /// one stream ordered by when they were played.
+    ///
+    /// `configure` sets up each user's [`GetRecentTracks`]. Every user is
+    /// walked in the direction given by `oldest_first`, whatever `configure`
+    /// sets, so that the streams can be merged.
+    pub async fn users_get_recent_tracks(
+        &mut self,
+        users: &[&str],
+        oldest_first: bool,
+        configure: impl for<'a> Fn(GetRecentTracks<'a>) -> GetRecentTracks<'a>,
+    ) -> LastFmResult<BoxStream<'static, LastFmResult<UserTrack>>> {
+        let requests: Vec<_> = users
+            .iter()
+            .map(|user| {
+                configure(self.user_get_recent_tracks(user)).with_oldest_first(oldest_first)
+            })
+            .collect();
+
+        let streams = try_join_all(requests.into_iter().map(|v| v.fetch())).await?;
+
+        Ok(merge_recent_tracks(
+            users.iter().copied().zip(streams),
+            oldest_first,
+        ))
+    }
+}
+
+/// Merges streams of recent tracks, each already in the order given by
+/// `oldest_first`, into one stream in that order.
+///
+/// A now playing track counts as played after everything else. An error is
+/// passed on as soon as it is met and the other streams carry on.
+pub fn merge_recent_tracks<I, U, S>(
+    streams: impl IntoIterator<Item = (U, S)>,
+    oldest_first: bool,
+) -> BoxStream<'static, LastFmResult<UserTrack<I>>>
+where
+    I: RecentTrack,
+    U: Into<Arc<str>>,
+    S: Stream<Item = LastFmResult<I>> + Send + 'static,
+{
+    let sources = streams
+        .into_iter()
+        .map(|(user, stream)| Source {
+            user: user.into(),
+            stream: stream.boxed(),
+            head: None,
+        })
+        .collect();
+
+    let merge = Merge {
+        sources,
+        oldest_first,
+    };
+
+    stream::unfold(merge, |mut v| async move {
+        v.next().await.map(|item| (item, v))
+    })
+    .boxed()
+}
+
+struct Source<I> {
+    user: Arc<str>,
+    stream: BoxStream<'static, LastFmResult<I>>,
+    /// The next item of the stream, once pulled.
+    head: Option<LastFmResult<I>>,
+}
+
+impl<I> Source<I> {
+    async fn fill(&mut self) {
+        if self.head.is_none() {
+            self.head = self.stream.next().await;
+        }
+    }
+}
+
+struct Merge<I> {
+    sources: Vec<Source<I>>,
+    oldest_first: bool,
+}
+
+impl<I: RecentTrack> Merge<I> {
+    async fn next(&mut self) -> Option<LastFmResult<UserTrack<I>>> {
+        future::join_all(self.sources.iter_mut().map(Source::fill)).await;
+
+        // A source without a head after being filled has run out.
+        self.sources.retain(|v| v.head.is_some());
+
+        let next = match self
+            .sources
+            .iter()
+            .position(|v| matches!(v.head, Some(Err(_))))
+        {
+            Some(i) => i,
+            None => self.earliest_or_latest()?,
+        };
+
+        let source = &mut self.sources[next];
+
+        source.head.take().map(|v| {
+            v.map(|track| UserTrack {
+                user: source.user.clone(),
+                track,
+            })
+        })
+    }
+
+    /// The source whose head goes next among those that hold a track.
+    fn earliest_or_latest(&self) -> Option<usize> {
+        let mut next: Option<(usize, i64)> = None;
+
+        for (i, source) in self.sources.iter().enumerate() {
+            let Some(Ok(track)) = &source.head else {
+                continue;
+            };
+
+            let key = track.played_at().map_or(i64::MAX, |v| v.timestamp());
+
+            let better = match next {
+                None => true,
+                Some((_, best)) if self.oldest_first => key < best,
+                Some((_, best)) => key > best,
+            };
+
+            if better {
+                next = Some((i, key));
+            }
+        }
+
+        next.map(|(i, _)| i)
+    }
+}
@@ -1,14 +1,21 @@
 pub mod get_recent_tracks;
+pub mod merged_recent_tracks;
+
+use std::sync::Arc;
 
 use reqwest::Method;
 use serde::Deserialize;
 
 use crate::{
     LastFm, RequestComponent,
-    authentication::{Enables, ReadPublic},
-    error::{LastFmResult, response::Response},
+    authentication::{Enables, ReadPublic, Session},
+    error::LastFmResult,
     page::{Paginated, PaginatedBuilder, PaginationConfig},
-    types::user::{Friend, User},
+    request::TopTagsResponse,
+    types::{
+        tag::{PersonalTagging, TagWithCount, TaggingType},
+        user::{AuthenticatedUser, Friend, User},
+    },
 };
 
 #[derive(Deserialize)]
@@ -20,11 +27,8 @@ impl<T: RequestComponent + Enables<ReadPublic>> LastFm<T> {
     pub async fn user_get_info(&mut self, user: &str) -> LastFmResult<User> {
         self.request(Method::GET, "user.getinfo")
             .query(&[("user", user)])
-            .send()
-            .await?
-            .json::<Response<UserGetInfoResponse>>()
-            .await?
-            .into_result()
+            .fetch::<UserGetInfoResponse>()
+            .await
             .map(|v| v.user)
     }
 
@@ -45,4 +49,67 @@ impl<T: RequestComponent + Enables<ReadPublic>> LastFm<T> {
             .paginated::<Friend>("friends", "user", config)
             .await
     }
+
+    /// Like [`LastFm::user_get_friends_with`], but each [`Friend`] also carries
+    /// their most recently scrobbled track.
+    pub async fn user_get_friends_with_recent_tracks(
+        &mut self,
+        user: &str,
+        config: PaginationConfig,
+    ) -> LastFmResult<Paginated<Friend>> {
+        self.request(Method::GET, "user.getfriends")
+            .query(&[("user", user), ("recenttracks", "1")])
+            .paginated::<Friend>("friends", "user", config)
+            .await
+    }
+
+    pub async fn user_get_top_tags(&mut self, user: &str) -> LastFmResult<Vec<TagWithCount>> {
+        self.request(Method::GET, "user.gettoptags")
+            .query(&[("user", user)])
+            .fetch::<TopTagsResponse>()
+            .await
+            .map(TopTagsResponse::into_tags)
+    }
+
+    pub async fn user_get_personal_tags(
+        &mut self,
+        user: &str,
+        tag: &str,
+        tagging_type: TaggingType,
+    ) -> LastFmResult<Paginated<PersonalTagging>> {
+        self.user_get_personal_tags_with(user, tag, tagging_type, Default::default())
+            .await
+    }
+
+    pub async fn user_get_personal_tags_with(
+        &mut self,
+        user: &str,
+        tag: &str,
+        tagging_type: TaggingType,
+        config: PaginationConfig,
+    ) -> LastFmResult<Paginated<PersonalTagging>> {
+        self.request(Method::GET, "user.getpersonaltags")
+            .query(&[
+                ("user", user),
+                ("tag", tag),
+                ("taggingtype", tagging_type.as_str()),
+            ])
+            .paginated::<PersonalTagging>("taggings", tagging_type.content_path(), config)
+            .await
+    }
+}
+
+impl<T: RequestComponent + Session> LastFm<T> {
+    /// Fetches the profile of the user the session belongs to.
+    pub async fn user_get_self(&mut self) -> LastFmResult<AuthenticatedUser> {
+        let username = Arc::from(self.authentication_component.username());
+
+        self.request(Method::GET, "user.getinfo")
+            .fetch::<UserGetInfoResponse>()
+            .await
+            .map(|v| AuthenticatedUser {
+                user: v.user,
+                username,
+            })
+    }
 }
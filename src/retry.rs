@@ -0,0 +1,50 @@
+use std::time::Duration;
+
+use crate::error::Error;
+
+/// When and how often a failed request is sent again.
+///
+/// Only failures that are [`Error::is_retryable`] are retried.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+
+    /// The wait before the first retry, doubled for each one after it.
+    pub initial_backoff: Duration,
+
+    pub max_backoff: Duration,
+
+    /// Also retry requests that are not idempotent, such as scrobbles, which
+    /// risks them taking effect twice.
+    pub retry_writes: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            retry_writes: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Whether to retry after `error` on attempt `attempt`, counting from 0.
+    pub(crate) fn should_retry(&self, error: &Error, attempt: u32, idempotent: bool) -> bool {
+        attempt < self.max_retries && (idempotent || self.retry_writes) && error.is_retryable()
+    }
+
+    /// The wait before retry `attempt`, counting from 0. Somewhere between
+    /// half and all of the exponential backoff, so that clients that failed
+    /// together do not retry together.
+    pub(crate) fn backoff(&self, attempt: u32) -> Duration {
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_backoff);
+
+        backoff.mul_f64(0.5 + fastrand::f64() / 2.0)
+    }
+}
@@ -9,6 +9,7 @@ pub enum ImageSize {
     Medium,
     Large,
     ExtraLarge,
+    Mega,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -1,6 +1,9 @@
+use std::sync::Arc;
+
 use serde::{Deserialize, Deserializer, de};
 
 pub mod image;
+pub mod tag;
 pub mod track;
 pub mod user;
 
@@ -12,3 +15,13 @@ pub fn bool_from_strnum<'de, D: Deserializer<'de>>(d: D) -> Result<bool, D::Erro
         _ => Err(de::Error::custom(format!("invalid boolean: {s}"))),
     }
 }
+
+pub(crate) fn de_opt_arcstr_empty<'de, D: Deserializer<'de>>(
+    d: D,
+) -> Result<Option<Arc<str>>, D::Error> {
+    let opt = Option::<String>::deserialize(d)?;
+    Ok(opt.and_then(|s| {
+        let s = s.trim();
+        (!s.is_empty()).then(|| Arc::<str>::from(s.to_owned()))
+    }))
+}
@@ -0,0 +1,115 @@
+use std::sync::Arc;
+
+use reqwest::Url;
+use serde::Deserialize;
+use serde_with::{DisplayFromStr, PickFirst, serde_as};
+
+use super::de_opt_arcstr_empty;
+use crate::types::image::Image;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Tag {
+    pub name: Arc<str>,
+
+    #[serde(default)]
+    pub url: Option<Url>,
+}
+
+/// A tag weighted by how often it has been applied.
+#[serde_as]
+#[derive(Debug, Deserialize, Clone)]
+pub struct TagWithCount {
+    pub name: Arc<str>,
+
+    #[serde_as(as = "PickFirst<(_, DisplayFromStr)>")]
+    pub count: u32,
+
+    #[serde(default)]
+    pub url: Option<Url>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaggingType {
+    Artist,
+    Album,
+    Track,
+}
+
+impl TaggingType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Artist => "artist",
+            Self::Album => "album",
+            Self::Track => "track",
+        }
+    }
+
+    /// The `root.content` path of the items in a `user.getPersonalTags` page.
+    pub(crate) fn content_path(&self) -> &'static str {
+        match self {
+            Self::Artist => "artists.artist",
+            Self::Album => "albums.album",
+            Self::Track => "tracks.track",
+        }
+    }
+}
+
+/// An item tagged by a user, as returned by `user.getPersonalTags`.
+///
+/// The variant is the [`TaggingType`] that was asked for, taken from the key
+/// the items sit under rather than guessed from their shape.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "lowercase")]
+pub enum PersonalTagging {
+    Artist(TaggedArtist),
+    Album(TaggedAlbum),
+    Track(TaggedTrack),
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct TaggedArtist {
+    pub name: Arc<str>,
+
+    #[serde(default)]
+    pub url: Option<Url>,
+
+    #[serde(default)]
+    pub image: Vec<Image>,
+
+    #[serde(default, rename = "mbid", deserialize_with = "de_opt_arcstr_empty")]
+    pub musicbrainz_id: Option<Arc<str>>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct TaggedAlbum {
+    pub name: Arc<str>,
+    pub artist: TaggedArtist,
+
+    #[serde(default)]
+    pub url: Option<Url>,
+
+    #[serde(default)]
+    pub image: Vec<Image>,
+
+    #[serde(default, rename = "mbid", deserialize_with = "de_opt_arcstr_empty")]
+    pub musicbrainz_id: Option<Arc<str>>,
+}
+
+#[serde_as]
+#[derive(Debug, Deserialize, Clone)]
+pub struct TaggedTrack {
+    pub name: Arc<str>,
+    pub artist: TaggedArtist,
+
+    #[serde_as(as = "PickFirst<(_, DisplayFromStr)>")]
+    pub duration: u32,
+
+    #[serde(default)]
+    pub url: Option<Url>,
+
+    #[serde(default)]
+    pub image: Vec<Image>,
+
+    #[serde(default, rename = "mbid", deserialize_with = "de_opt_arcstr_empty")]
+    pub musicbrainz_id: Option<Arc<str>>,
+}
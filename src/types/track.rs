@@ -1,9 +1,9 @@
 use std::sync::Arc;
 
-use super::bool_from_strnum;
+use super::{bool_from_strnum, de_opt_arcstr_empty};
 use chrono::{DateTime, Utc};
 use reqwest::Url;
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, de::DeserializeOwned};
 use serde_json::Value;
 use serde_with::serde_as;
 
@@ -61,27 +61,135 @@ pub struct TrackAlbum {
     pub title: Arc<str>,
 }
 
-fn de_opt_arcstr_empty<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Arc<str>>, D::Error> {
-    let opt = Option::<String>::deserialize(d)?;
-    Ok(opt.and_then(|s| {
-        let s = s.trim();
-        (!s.is_empty()).then(|| Arc::<str>::from(s.to_owned()))
-    }))
+/// A track in the non-extended `user.getRecentTracks` shape, which carries
+/// no artist images and no loved status.
+#[serde_as]
+#[derive(Debug, Deserialize, Clone)]
+pub struct BasicTrack {
+    pub artist: BasicTrackArtist,
+    pub album: TrackAlbum,
+    pub name: Arc<str>,
+
+    pub url: Url,
+
+    pub image: Vec<Image>,
+
+    #[serde(default, rename = "mbid", deserialize_with = "de_opt_arcstr_empty")]
+    pub musicbrainz_id: Option<Arc<str>>,
+
+    #[serde(deserialize_with = "bool_from_strnum")]
+    pub streamable: bool,
+
+    #[serde(default, deserialize_with = "de_played_at", rename = "date")]
+    pub played_at: Option<DateTime<Utc>>,
+
+    #[serde(default, rename = "@attr", deserialize_with = "de_now_playing")]
+    pub now_playing: bool,
+}
+
+#[serde_as]
+#[derive(Debug, Deserialize, Clone)]
+pub struct BasicTrackArtist {
+    #[serde(default, rename = "mbid", deserialize_with = "de_opt_arcstr_empty")]
+    pub musicbrainz_id: Option<Arc<str>>,
+
+    #[serde(rename = "#text")]
+    pub name: Arc<str>,
+}
+
+/// The most recent track of a [`Friend`](crate::types::user::Friend), which
+/// carries no album, images or loved status.
+#[serde_as]
+#[derive(Debug, Deserialize, Clone)]
+pub struct FriendTrack {
+    pub artist: FriendTrackArtist,
+    pub name: Arc<str>,
+
+    pub url: Url,
+
+    #[serde(default, rename = "mbid", deserialize_with = "de_opt_arcstr_empty")]
+    pub musicbrainz_id: Option<Arc<str>>,
+
+    #[serde(default, deserialize_with = "de_played_at", rename = "@attr")]
+    pub played_at: Option<DateTime<Utc>>,
+}
+
+#[serde_as]
+#[derive(Debug, Deserialize, Clone)]
+pub struct FriendTrackArtist {
+    pub name: Arc<str>,
+
+    #[serde(default)]
+    pub url: Option<Url>,
+
+    #[serde(default, rename = "mbid", deserialize_with = "de_opt_arcstr_empty")]
+    pub musicbrainz_id: Option<Arc<str>>,
+}
+
+/// The fields shared by every shape of a `user.getRecentTracks` item.
+pub trait RecentTrack: DeserializeOwned + Send + 'static {
+    /// Whether this is the `extended=1` shape.
+    const EXTENDED: bool;
+
+    fn name(&self) -> &str;
+    fn artist_name(&self) -> &str;
+    fn played_at(&self) -> Option<DateTime<Utc>>;
+    fn now_playing(&self) -> bool;
+}
+
+impl RecentTrack for Track {
+    const EXTENDED: bool = true;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn artist_name(&self) -> &str {
+        &self.artist.name
+    }
+
+    fn played_at(&self) -> Option<DateTime<Utc>> {
+        self.played_at
+    }
+
+    fn now_playing(&self) -> bool {
+        self.now_playing
+    }
+}
+
+impl RecentTrack for BasicTrack {
+    const EXTENDED: bool = false;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn artist_name(&self) -> &str {
+        &self.artist.name
+    }
+
+    fn played_at(&self) -> Option<DateTime<Utc>> {
+        self.played_at
+    }
+
+    fn now_playing(&self) -> bool {
+        self.now_playing
+    }
 }
 
 fn de_played_at<'de, D: Deserializer<'de>>(d: D) -> Result<Option<DateTime<Utc>>, D::Error> {
     let v = Option::<Value>::deserialize(d)?;
-    if let Some(Value::Object(map)) = v {
-        if let Some(uts_val) = map.get("uts") {
-            let uts_i64 = match uts_val {
-                Value::String(s) => s.parse::<i64>().map_err(serde::de::Error::custom)?,
-                Value::Number(n) => n
-                    .as_i64()
-                    .ok_or_else(|| serde::de::Error::custom("uts not an i64"))?,
-                _ => return Ok(None),
-            };
-            return Ok(DateTime::from_timestamp(uts_i64, 0));
-        }
+    if let Some(Value::Object(map)) = v
+        && let Some(uts_val) = map.get("uts")
+    {
+        let uts_i64 = match uts_val {
+            Value::String(s) => s.parse::<i64>().map_err(serde::de::Error::custom)?,
+            Value::Number(n) => n
+                .as_i64()
+                .ok_or_else(|| serde::de::Error::custom("uts not an i64"))?,
+            _ => return Ok(None),
+        };
+        return Ok(DateTime::from_timestamp(uts_i64, 0));
     }
     Ok(None)
 }
@@ -8,7 +8,7 @@ use serde::{Deserialize, Deserializer};
 use serde_with::serde_as;
 use serde_with::{DisplayFromStr, TimestampSeconds};
 
-use crate::types::image::Image;
+use crate::types::{image::Image, track::FriendTrack};
 
 #[serde_as]
 #[derive(Deserialize, Clone)]
@@ -51,6 +51,14 @@ pub struct User {
     pub registered: DateTime<Utc>,
 }
 
+/// The user behind the current session, as returned by
+/// [`LastFm::user_get_self`](crate::LastFm::user_get_self).
+#[derive(Clone)]
+pub struct AuthenticatedUser {
+    pub user: User,
+    pub username: Arc<str>,
+}
+
 #[serde_as]
 #[derive(Deserialize, Clone)]
 pub struct Friend {
@@ -70,6 +78,10 @@ pub struct Friend {
 
     #[serde(deserialize_with = "de_registered")]
     pub registered: DateTime<Utc>,
+
+    /// Only present when requested with `recenttracks=1`.
+    #[serde(default, rename = "recenttrack")]
+    pub recent_track: Option<FriendTrack>,
 }
 
 #[serde_as]
@@ -0,0 +1,223 @@
+mod common;
+
+use std::{num::NonZeroUsize, time::Duration};
+
+use bytes::Bytes;
+
+use lastfm_rs_api::{
+    LastFm,
+    authentication::{public::PublicAuthentication, session::SessionAuthentication},
+    cache::{Cache, CacheEntry, CachePolicy, disk::DiskCache, memory::MemoryCache},
+};
+use reqwest::Method;
+use serde_json::Value;
+use wiremock::{
+    Mock, MockServer,
+    matchers::{method, query_param},
+};
+
+use common::{api_error, artist, requests, serve};
+
+fn memory() -> MemoryCache {
+    MemoryCache::new(NonZeroUsize::new(16).unwrap())
+}
+
+fn client(server: &MockServer, policy: CachePolicy) -> LastFm<PublicAuthentication> {
+    common::public_client(server).with_cache(policy)
+}
+
+async fn get(client: &mut LastFm<PublicAuthentication>, parameters: &[(&str, &str)]) -> Value {
+    client
+        .request(Method::GET, "artist.getInfo")
+        .query(parameters)
+        .fetch::<Value>()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn answers_fresh_calls_from_the_cache() {
+    let server = MockServer::start().await;
+    serve(&server, artist("Cher"), 1).await;
+    serve(&server, artist("Other"), u64::MAX).await;
+
+    let policy = CachePolicy::new(memory()).with_ttl("artist.getInfo", Duration::from_secs(60));
+    let mut client = client(&server, policy);
+
+    let first = get(&mut client, &[("artist", "Cher"), ("lang", "en")]).await;
+    let second = get(&mut client, &[("lang", "en"), ("artist", "Cher")]).await;
+
+    assert_eq!(first, second);
+    assert_eq!(requests(&server).await, 1);
+
+    get(&mut client, &[("artist", "Cher"), ("lang", "de")]).await;
+    assert_eq!(requests(&server).await, 2);
+}
+
+#[tokio::test]
+async fn leaves_methods_without_a_ttl_alone() {
+    let server = MockServer::start().await;
+    serve(&server, artist("Cher"), u64::MAX).await;
+
+    let policy = CachePolicy::new(memory()).with_ttl("user.getTopArtists", Duration::from_secs(60));
+    let mut client = client(&server, policy);
+
+    get(&mut client, &[("artist", "Cher")]).await;
+    get(&mut client, &[("artist", "Cher")]).await;
+
+    assert_eq!(requests(&server).await, 2);
+}
+
+#[tokio::test]
+async fn refetches_once_expired() {
+    let server = MockServer::start().await;
+    serve(&server, artist("Cher"), u64::MAX).await;
+
+    let policy = CachePolicy::new(memory()).with_default_ttl(Duration::ZERO);
+    let mut client = client(&server, policy);
+
+    get(&mut client, &[("artist", "Cher")]).await;
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    get(&mut client, &[("artist", "Cher")]).await;
+
+    assert_eq!(requests(&server).await, 2);
+}
+
+#[tokio::test]
+async fn serves_stale_entries_while_offline() {
+    let server = MockServer::start().await;
+    serve(&server, artist("Cher"), 1).await;
+    serve(&server, api_error(11), u64::MAX).await;
+
+    let policy = CachePolicy::new(memory()).with_default_ttl(Duration::ZERO);
+    let mut client = client(&server, policy);
+
+    let fresh = get(&mut client, &[("artist", "Cher")]).await;
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    let stale = get(&mut client, &[("artist", "Cher")]).await;
+
+    assert_eq!(fresh, stale);
+    assert_eq!(requests(&server).await, 2);
+}
+
+#[tokio::test]
+async fn does_not_hide_other_errors() {
+    let server = MockServer::start().await;
+    serve(&server, artist("Cher"), 1).await;
+    serve(&server, api_error(6), u64::MAX).await;
+
+    let policy = CachePolicy::new(memory()).with_default_ttl(Duration::ZERO);
+    let mut client = client(&server, policy);
+
+    get(&mut client, &[("artist", "Cher")]).await;
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    let result = client
+        .request(Method::GET, "artist.getInfo")
+        .query(&[("artist", "Cher")])
+        .fetch::<Value>()
+        .await;
+
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn keeps_entries_on_disk() {
+    let dir = std::env::temp_dir().join(format!("lastfm-cache-{}", std::process::id()));
+    let server = MockServer::start().await;
+    serve(&server, artist("Cher"), 1).await;
+
+    let policy = || {
+        CachePolicy::new(DiskCache::new(&dir).unwrap())
+            .with_ttl("artist.getinfo", Duration::from_secs(60))
+    };
+
+    let first = get(&mut client(&server, policy()), &[("artist", "Cher")]).await;
+    let second = get(&mut client(&server, policy()), &[("artist", "Cher")]).await;
+
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(first, second);
+    assert_eq!(requests(&server).await, 1);
+}
+
+fn files_in(dir: &std::path::Path) -> usize {
+    std::fs::read_dir(dir).unwrap().count()
+}
+
+#[tokio::test]
+async fn removes_disk_entries_read_too_old_to_use() {
+    let dir = std::env::temp_dir().join(format!("lastfm-expiry-{}", std::process::id()));
+    let server = MockServer::start().await;
+    serve(&server, artist("Cher"), 1).await;
+
+    let mut client = client(
+        &server,
+        CachePolicy::new(DiskCache::new(&dir).unwrap())
+            .with_ttl("artist.getinfo", Duration::ZERO)
+            .with_stale_for(Duration::ZERO),
+    );
+
+    get(&mut client, &[("artist", "Cher")]).await;
+    assert_eq!(files_in(&dir), 1);
+
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    let result = client
+        .request(Method::GET, "artist.getInfo")
+        .query(&[("artist", "Cher")])
+        .fetch::<Value>()
+        .await;
+    let left = files_in(&dir);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(result.is_err());
+    assert_eq!(left, 0);
+}
+
+#[test]
+fn purges_old_disk_entries() {
+    let dir = std::env::temp_dir().join(format!("lastfm-purge-{}", std::process::id()));
+    let cache = DiskCache::new(&dir).unwrap();
+    cache.put("key", CacheEntry::new(Bytes::from_static(b"{}")));
+
+    cache.purge(Duration::from_secs(60)).unwrap();
+    assert_eq!(files_in(&dir), 1);
+
+    cache.purge(Duration::ZERO).unwrap();
+    let left = files_in(&dir);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(left, 0);
+}
+
+#[tokio::test]
+async fn keeps_sessions_apart() {
+    let server = MockServer::start().await;
+    for user in ["alice", "bob"] {
+        Mock::given(method("GET"))
+            .and(query_param("sk", format!("{user}-key")))
+            .respond_with(artist(user))
+            .mount(&server)
+            .await;
+    }
+
+    let shared = common::client(&server)
+        .with_cache(CachePolicy::new(memory()).with_ttl("user.getInfo", Duration::from_secs(60)));
+
+    let mut got = Vec::new();
+    for user in ["alice", "bob", "alice"] {
+        let session = SessionAuthentication::new("key", "secret", &format!("{user}-key"), user);
+        let body = shared
+            .clone()
+            .with_authentication(session)
+            .request(Method::GET, "user.getInfo")
+            .fetch::<Value>()
+            .await
+            .unwrap();
+
+        got.push(body["artist"]["name"].as_str().unwrap().to_owned());
+    }
+
+    assert_eq!(got, ["alice", "bob", "alice"]);
+    assert_eq!(requests(&server).await, 2);
+}
@@ -0,0 +1,145 @@
+mod common;
+
+use std::time::Duration;
+
+use futures::future::join_all;
+use lastfm_rs_api::{LastFm, circuit_breaker::CircuitBreakerPolicy, error::Error};
+use reqwest::Method;
+use serde_json::Value;
+use wiremock::{Mock, MockServer, matchers::method};
+
+use common::{api_error, ok, requests, serve};
+
+const COOLDOWN: Duration = Duration::from_millis(100);
+
+fn client(base_url: &str) -> LastFm<()> {
+    common::client_at(base_url).with_circuit_breaker(CircuitBreakerPolicy {
+        failure_threshold: 2,
+        cooldown: COOLDOWN,
+    })
+}
+
+async fn get(client: &mut LastFm<()>) -> Result<Value, Error> {
+    client
+        .request(Method::GET, "artist.getInfo")
+        .fetch::<Value>()
+        .await
+}
+
+fn is_open(result: Result<Value, Error>) -> bool {
+    matches!(result.unwrap_err().inner(), Error::CircuitOpen { .. })
+}
+
+#[tokio::test]
+async fn opens_after_consecutive_outages() {
+    let server = MockServer::start().await;
+    serve(&server, api_error(11), 1).await;
+    serve(&server, api_error(16), 1).await;
+    serve(&server, ok(), u64::MAX).await;
+
+    let mut client = client(&server.uri());
+    get(&mut client).await.unwrap_err();
+    get(&mut client).await.unwrap_err();
+
+    assert!(is_open(get(&mut client).await));
+    assert_eq!(requests(&server).await, 2);
+}
+
+#[tokio::test]
+async fn other_outcomes_reset_the_count() {
+    let server = MockServer::start().await;
+    serve(&server, api_error(11), 1).await;
+    serve(&server, api_error(6), 1).await;
+    serve(&server, api_error(11), 1).await;
+    serve(&server, ok(), u64::MAX).await;
+
+    let mut client = client(&server.uri());
+    for _ in 0..3 {
+        get(&mut client).await.unwrap_err();
+    }
+
+    get(&mut client).await.unwrap();
+    assert_eq!(requests(&server).await, 4);
+}
+
+#[tokio::test]
+async fn closes_once_a_probe_succeeds() {
+    let server = MockServer::start().await;
+    serve(&server, api_error(11), 2).await;
+    serve(&server, ok(), u64::MAX).await;
+
+    let mut client = client(&server.uri());
+    get(&mut client).await.unwrap_err();
+    get(&mut client).await.unwrap_err();
+    assert!(is_open(get(&mut client).await));
+
+    tokio::time::sleep(COOLDOWN).await;
+
+    get(&mut client).await.unwrap();
+    get(&mut client).await.unwrap();
+    assert_eq!(requests(&server).await, 4);
+}
+
+#[tokio::test]
+async fn reopens_when_a_probe_fails() {
+    let server = MockServer::start().await;
+    serve(&server, api_error(11), u64::MAX).await;
+
+    let mut client = client(&server.uri());
+    get(&mut client).await.unwrap_err();
+    get(&mut client).await.unwrap_err();
+
+    tokio::time::sleep(COOLDOWN).await;
+
+    let probe = get(&mut client).await.unwrap_err();
+    assert!(matches!(probe.inner(), Error::ApiError(_)), "{probe}");
+    assert!(is_open(get(&mut client).await));
+    assert_eq!(requests(&server).await, 3);
+}
+
+#[tokio::test]
+async fn calls_sharing_a_request_count_once() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(api_error(11).set_delay(Duration::from_millis(200)))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    serve(&server, ok(), u64::MAX).await;
+
+    let mut client = client(&server.uri());
+    let calls = (0..3).map(|_| {
+        client
+            .request(Method::GET, "artist.getInfo")
+            .fetch::<Value>()
+    });
+
+    for result in join_all(calls.collect::<Vec<_>>()).await {
+        let error = result.unwrap_err();
+        assert!(matches!(error.inner(), Error::ApiError(_)), "{error}");
+    }
+    assert_eq!(requests(&server).await, 1);
+
+    get(&mut client).await.unwrap();
+    assert_eq!(requests(&server).await, 2);
+}
+
+#[tokio::test]
+async fn counts_failed_connections() {
+    // Nothing listens on a port that was just given back.
+    let address = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+
+    let mut client = client(&format!("http://{address}"));
+    get(&mut client).await.unwrap_err();
+    get(&mut client).await.unwrap_err();
+
+    let error = get(&mut client).await.unwrap_err();
+    assert!(
+        matches!(error.inner(), Error::CircuitOpen { .. }),
+        "{error}"
+    );
+    assert!(!error.is_retryable());
+}
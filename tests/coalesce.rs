@@ -0,0 +1,135 @@
+mod common;
+
+use std::time::Duration;
+
+use futures::future::join_all;
+use lastfm_rs_api::{
+    LastFm, authentication::public::PublicAuthentication, error::Error, rate_limit::RateLimit,
+};
+use reqwest::Method;
+use serde_json::{Value, json};
+use wiremock::{Mock, MockServer, ResponseTemplate, matchers::method};
+
+use common::{public_client, requests};
+
+fn slow(template: ResponseTemplate) -> ResponseTemplate {
+    template.set_delay(Duration::from_millis(200))
+}
+
+async fn get_all(
+    client: &mut LastFm<PublicAuthentication>,
+    artists: &[&str],
+) -> Vec<Result<Value, Error>> {
+    let calls = artists.iter().map(|artist| {
+        client
+            .request(Method::GET, "artist.getInfo")
+            .query(&[("artist", artist)])
+            .fetch::<Value>()
+    });
+
+    join_all(calls.collect::<Vec<_>>()).await
+}
+
+#[tokio::test]
+async fn identical_calls_in_flight_share_one_request() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(slow(
+            ResponseTemplate::new(200).set_body_json(json!({ "artist": { "name": "Cher" } })),
+        ))
+        .mount(&server)
+        .await;
+
+    let mut client = public_client(&server);
+    let results = get_all(&mut client, &["Cher"; 5]).await;
+
+    assert_eq!(requests(&server).await, 1);
+    for result in results {
+        assert_eq!(result.unwrap()["artist"]["name"], "Cher");
+    }
+
+    // Once the flight is over, the next call goes out again.
+    get_all(&mut client, &["Cher"]).await;
+    assert_eq!(requests(&server).await, 2);
+}
+
+#[tokio::test]
+async fn different_calls_are_sent_separately() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(slow(
+            ResponseTemplate::new(200).set_body_json(json!({ "artist": {} })),
+        ))
+        .mount(&server)
+        .await;
+
+    let mut client = public_client(&server);
+    get_all(&mut client, &["Cher", "Madonna", "Cher"]).await;
+
+    assert_eq!(requests(&server).await, 2);
+}
+
+#[tokio::test]
+async fn every_waiter_sees_a_shared_failure() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(slow(ResponseTemplate::new(503)))
+        .mount(&server)
+        .await;
+
+    let mut client = public_client(&server);
+    let results = get_all(&mut client, &["Cher"; 3]).await;
+
+    assert_eq!(requests(&server).await, 1);
+    for result in results {
+        let error = result.unwrap_err();
+        assert!(error.is_retryable(), "{error}");
+        assert!(matches!(error.inner(), Error::HttpStatus(status) if status.as_u16() == 503));
+    }
+}
+
+#[tokio::test]
+async fn flights_everyone_gave_up_on_leave_the_rate_limit_queue() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+        .mount(&server)
+        .await;
+
+    let mut client = LastFm::new()
+        .with_base_url(&server.uri())
+        .with_rate_limit(RateLimit {
+            requests: 1,
+            per: Duration::from_millis(200),
+        });
+    let mut get = |method| client.request(Method::GET, method).fetch::<Value>();
+
+    get("a.get").await.unwrap();
+    let gave_up = tokio::time::timeout(Duration::from_millis(20), get("b.get")).await;
+    assert!(gave_up.is_err());
+
+    tokio::time::timeout(Duration::from_secs(2), get("c.get"))
+        .await
+        .expect("the abandoned flight to have left the queue")
+        .unwrap();
+    assert_eq!(
+        common::query_values(&server, "method").await,
+        ["a.get", "c.get"]
+    );
+}
+
+#[tokio::test]
+async fn coalescing_can_be_turned_off() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(slow(
+            ResponseTemplate::new(200).set_body_json(json!({ "artist": {} })),
+        ))
+        .mount(&server)
+        .await;
+
+    let mut client = public_client(&server).without_request_coalescing();
+    get_all(&mut client, &["Cher"; 3]).await;
+
+    assert_eq!(requests(&server).await, 3);
+}
@@ -0,0 +1,82 @@
+//! Fixtures shared by the integration tests. Each test crate uses only some
+//! of them.
+#![allow(dead_code)]
+
+use lastfm_rs_api::{LastFm, authentication::public::PublicAuthentication};
+use serde_json::json;
+use wiremock::{Mock, MockServer, ResponseTemplate, matchers::method};
+
+/// A client that sends to `base_url` without waiting on a rate limit.
+pub fn client_at(base_url: &str) -> LastFm<()> {
+    LastFm::new().with_base_url(base_url).without_rate_limit()
+}
+
+pub fn client(server: &MockServer) -> LastFm<()> {
+    client_at(&server.uri())
+}
+
+/// [`client`] with an API key, for the calls that need one.
+pub fn public_client(server: &MockServer) -> LastFm<PublicAuthentication> {
+    client(server).with_authentication(PublicAuthentication::new("key"))
+}
+
+/// A Last.fm error response, sent with HTTP 200 like Last.fm often does.
+pub fn api_error(code: u32) -> ResponseTemplate {
+    api_error_with_status(200, code)
+}
+
+pub fn api_error_with_status(status: u16, code: u32) -> ResponseTemplate {
+    ResponseTemplate::new(status).set_body_json(json!({ "error": code, "message": "Nope" }))
+}
+
+/// A non-extended `user.getRecentTracks` item, or the now playing track
+/// without a time.
+pub fn recent_track(name: &str, played_at: Option<i64>) -> String {
+    let when = match played_at {
+        Some(uts) => format!(r##""date":{{"uts":"{uts}","#text":""}}"##),
+        None => r#""@attr":{"nowplaying":"true"}"#.to_owned(),
+    };
+
+    format!(
+        r##"{{"artist":{{"mbid":"","#text":"Artist"}},"album":{{"mbid":"","#text":""}},"name":"{name}","url":"https://www.last.fm/","image":[],"mbid":"","streamable":"0",{when}}}"##
+    )
+}
+
+pub fn ok() -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(json!({ "ok": true }))
+}
+
+pub fn artist(name: &str) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(json!({ "artist": { "name": name } }))
+}
+
+/// Answers the next `times` `GET` requests with `template`. Mounted templates
+/// are tried in the order they were mounted.
+pub async fn serve(server: &MockServer, template: ResponseTemplate, times: u64) {
+    Mock::given(method("GET"))
+        .respond_with(template)
+        .up_to_n_times(times)
+        .mount(server)
+        .await;
+}
+
+pub async fn requests(server: &MockServer) -> usize {
+    server.received_requests().await.unwrap().len()
+}
+
+/// The value of the `key` parameter of each request the server got, in the
+/// order it got them.
+pub async fn query_values(server: &MockServer, key: &str) -> Vec<String> {
+    server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .filter_map(|v| {
+            v.url
+                .query_pairs()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.into_owned())
+        })
+        .collect()
+}
@@ -0,0 +1,185 @@
+mod common;
+
+use lastfm_rs_api::{
+    authentication::public::PublicAuthentication,
+    error::{
+        Error,
+        last_fm::{LastFmError, LastFmErrorResponse},
+    },
+    page::PaginatedBuilder,
+};
+use reqwest::{Method, StatusCode};
+use serde::Deserialize;
+use serde_json::Value;
+use wiremock::{MockServer, ResponseTemplate};
+
+use common::{api_error_with_status, serve};
+
+async fn failing(template: ResponseTemplate) -> Error {
+    let server = MockServer::start().await;
+    serve(&server, template, u64::MAX).await;
+
+    common::client(&server)
+        .with_authentication(PublicAuthentication::new("secret-key"))
+        .request(Method::GET, "user.getinfo")
+        .query(&[("user", "rj"), ("page", "3")])
+        .fetch::<Value>()
+        .await
+        .unwrap_err()
+}
+
+#[tokio::test]
+async fn carries_the_request_context() {
+    let error = failing(api_error_with_status(400, 6)).await;
+    let context = error.context().unwrap();
+
+    assert_eq!(context.method, "user.getinfo");
+    assert_eq!(context.page, Some(3));
+    assert_eq!(context.status, Some(StatusCode::BAD_REQUEST));
+    assert_eq!(context.parameters, [("user".to_owned(), "rj".to_owned())]);
+
+    let message = error.to_string();
+    assert!(
+        message.contains("user.getinfo page 3, HTTP 400"),
+        "{message}"
+    );
+}
+
+#[tokio::test]
+async fn never_shows_the_api_key() {
+    // Nothing listens on a port that was just freed.
+    let address = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+
+    let error = common::client_at(&format!("http://{address}"))
+        .with_authentication(PublicAuthentication::new("secret-key"))
+        .request(Method::GET, "user.getinfo")
+        .query(&[("api_key", "secret-key")])
+        .fetch::<Value>()
+        .await
+        .unwrap_err();
+
+    assert!(error.is_retryable());
+    assert!(!format!("{error} {error:?}").contains("secret-key"));
+}
+
+#[tokio::test]
+async fn classifies_api_errors() {
+    let error = failing(api_error_with_status(403, 10)).await;
+    assert!(error.is_auth_error());
+    assert!(error.is_client_error());
+    assert!(!error.is_retryable());
+
+    let error = failing(api_error_with_status(400, 6)).await;
+    assert!(!error.is_auth_error());
+    assert!(error.is_client_error());
+
+    let error = failing(api_error_with_status(500, 16)).await;
+    assert!(error.is_retryable());
+    assert!(!error.is_client_error());
+}
+
+#[tokio::test]
+async fn classifies_http_errors() {
+    let error = failing(ResponseTemplate::new(502)).await;
+    assert!(error.is_retryable());
+    assert_eq!(
+        error.context().unwrap().status,
+        Some(StatusCode::BAD_GATEWAY)
+    );
+
+    let error = failing(ResponseTemplate::new(401)).await;
+    assert!(error.is_auth_error());
+    assert!(!error.is_retryable());
+}
+
+#[derive(Debug, Deserialize)]
+struct Item {
+    #[allow(dead_code)]
+    count: u32,
+}
+
+#[tokio::test]
+async fn parse_errors_keep_the_path_and_body() {
+    let body = r#"{"things":{"thing":[{"count":1},{"count":"many"}],"@attr":{"page":"1","perPage":"2","totalPages":"1","total":"2"}}}"#;
+
+    let server = MockServer::start().await;
+    let template = ResponseTemplate::new(200).set_body_raw(body, "application/json");
+    serve(&server, template, u64::MAX).await;
+
+    let error = common::client(&server)
+        .request(Method::GET, "test.getthings")
+        .paginated::<Item>("things", "thing", Default::default())
+        .await
+        .err()
+        .unwrap();
+
+    let Error::ParseError(parse) = error.inner() else {
+        panic!("expected a parse error, got {error:?}");
+    };
+
+    assert_eq!(parse.path, "things.thing[1].count");
+    assert_eq!(parse.status, Some(StatusCode::OK));
+    assert_eq!(parse.body.as_deref(), Some(body));
+    assert!(error.to_string().contains("things.thing[1].count"));
+}
+
+#[tokio::test]
+async fn parse_errors_truncate_the_body() {
+    let body = format!("<html>{}</html>", "x".repeat(2000));
+    let error = failing(ResponseTemplate::new(200).set_body_string(body)).await;
+
+    let Error::ParseError(parse) = error.inner() else {
+        panic!("expected a parse error, got {error:?}");
+    };
+
+    assert!(parse.path.is_empty());
+    assert!(parse.body.as_ref().unwrap().len() < 600);
+    assert!(parse.body.as_ref().unwrap().starts_with("<html>"));
+}
+
+#[test]
+fn every_documented_code_has_a_variant() {
+    for code in (2..=29).filter(|v| ![12, 19, 28].contains(v)) {
+        let error = LastFmError::from(LastFmErrorResponse {
+            message: None,
+            error: code,
+        });
+
+        assert!(
+            !matches!(error, LastFmError::Unknown { .. }),
+            "{code} is unknown"
+        );
+        assert_eq!(error.code(), code);
+    }
+}
+
+#[test]
+fn hints_tell_a_private_profile_from_a_bad_key() {
+    let private = LastFmError::from(LastFmErrorResponse {
+        message: None,
+        error: 17,
+    });
+    let bad_key = LastFmError::from(LastFmErrorResponse {
+        message: None,
+        error: 10,
+    });
+
+    assert!(matches!(private, LastFmError::LoginRequired { .. }));
+    assert_ne!(private.hint(), bad_key.hint());
+    assert!(bad_key.is_auth_error());
+    assert!(!private.is_auth_error());
+}
+
+#[tokio::test]
+async fn api_errors_decode_in_any_key_order() {
+    let body = r#"{"message":"User not found","links":[],"error":6}"#;
+    let error = failing(ResponseTemplate::new(200).set_body_raw(body, "application/json")).await;
+
+    assert!(matches!(
+        error.inner(),
+        Error::ApiError(LastFmError::InvalidParameters { .. })
+    ));
+}
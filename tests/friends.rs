@@ -0,0 +1,77 @@
+mod common;
+
+use futures::TryStreamExt;
+use lastfm_rs_api::{page::PaginationConfig, types::user::Friend};
+use serde_json::{Value, json};
+use wiremock::{MockServer, ResponseTemplate};
+
+use common::{public_client, serve};
+
+fn friends(recent_track: Option<Value>) -> ResponseTemplate {
+    let mut friend = json!({
+        "name": "eartle",
+        "subscriber": "0",
+        "realname": "Michael Coffey",
+        "bootstrap": "0",
+        "image": [{ "size": "small", "#text": "" }],
+        "country": "United Kingdom",
+        "url": "https://www.last.fm/user/eartle",
+        "type": "user",
+        "registered": { "unixtime": 1152702557, "#text": "2006-07-12 11:09" },
+    });
+    if let Some(track) = recent_track {
+        friend["recenttrack"] = track;
+    }
+
+    ResponseTemplate::new(200).set_body_json(json!({
+        "friends": {
+            "user": [friend],
+            "@attr": { "user": "rj", "page": "1", "perPage": "50", "totalPages": "1", "total": "1" },
+        }
+    }))
+}
+
+async fn friends_of_rj(server: &MockServer) -> Vec<Friend> {
+    public_client(server)
+        .user_get_friends_with_recent_tracks("rj", PaginationConfig::default())
+        .await
+        .unwrap()
+        .send()
+        .try_collect()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn decodes_recent_tracks_of_friends() {
+    let server = MockServer::start().await;
+    let track = json!({
+        "artist": {
+            "url": "https://www.last.fm/music/Cher",
+            "name": "Cher",
+            "mbid": "bfcc6d75-a6a5-4bc6-8282-47aec8531818",
+        },
+        "name": "Believe",
+        "mbid": "",
+        "url": "https://www.last.fm/music/Cher/_/Believe",
+        "@attr": { "date": "Tue, 17 Oct 2023 08:26:40 +0000", "uts": "1697531200" },
+    });
+    serve(&server, friends(Some(track)), 1).await;
+
+    let friends = friends_of_rj(&server).await;
+    let track = friends[0].recent_track.as_ref().unwrap();
+
+    assert_eq!(&*track.name, "Believe");
+    assert_eq!(&*track.artist.name, "Cher");
+    assert_eq!(track.musicbrainz_id, None);
+    assert_eq!(track.played_at.unwrap().timestamp(), 1697531200);
+    assert_eq!(common::query_values(&server, "recenttracks").await, ["1"]);
+}
+
+#[tokio::test]
+async fn friends_without_a_recent_track_have_none() {
+    let server = MockServer::start().await;
+    serve(&server, friends(None), 1).await;
+
+    assert!(friends_of_rj(&server).await[0].recent_track.is_none());
+}
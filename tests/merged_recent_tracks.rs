@@ -0,0 +1,154 @@
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use futures::{StreamExt, TryStreamExt, stream};
+use lastfm_rs_api::{
+    error::{Error, LastFmResult},
+    request::user::merged_recent_tracks::merge_recent_tracks,
+    types::track::BasicTrack,
+};
+use wiremock::{
+    Mock, MockServer, ResponseTemplate,
+    matchers::{method, query_param},
+};
+
+fn track(name: &str, played_at: Option<i64>) -> LastFmResult<BasicTrack> {
+    Ok(serde_json::from_str(&common::recent_track(name, played_at)).unwrap())
+}
+
+fn played(items: &[(String, String)]) -> Vec<(&str, &str)> {
+    items
+        .iter()
+        .map(|(u, t)| (u.as_str(), t.as_str()))
+        .collect()
+}
+
+#[tokio::test]
+async fn merges_newest_first() {
+    let alice = stream::iter(vec![
+        track("np", None),
+        track("a3", Some(30)),
+        track("a1", Some(10)),
+    ]);
+    let bob = stream::iter(vec![track("b4", Some(40)), track("b2", Some(20))]);
+
+    let items: Vec<_> = merge_recent_tracks([("alice", alice), ("bob", bob)], false)
+        .map_ok(|v| (v.user.to_string(), v.track.name.to_string()))
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(
+        played(&items),
+        [
+            ("alice", "np"),
+            ("bob", "b4"),
+            ("alice", "a3"),
+            ("bob", "b2"),
+            ("alice", "a1"),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn merges_oldest_first() {
+    let alice = stream::iter(vec![track("a1", Some(10)), track("a3", Some(30))]);
+    let bob = stream::iter(vec![track("b2", Some(20)), track("b4", Some(40))]);
+
+    let items: Vec<_> = merge_recent_tracks([("alice", alice), ("bob", bob)], true)
+        .map_ok(|v| (v.user.to_string(), v.track.name.to_string()))
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(
+        played(&items),
+        [
+            ("alice", "a1"),
+            ("bob", "b2"),
+            ("alice", "a3"),
+            ("bob", "b4")
+        ]
+    );
+}
+
+#[tokio::test]
+async fn carries_on_past_an_error() {
+    let alice = stream::iter(vec![
+        track("a3", Some(30)),
+        Err(Error::InvalidPageSize {
+            method: "user.getrecenttracks".to_owned(),
+            page_size: 0,
+            max: 200,
+        }),
+    ]);
+    let bob = stream::iter(vec![track("b4", Some(40)), track("b2", Some(20))]);
+
+    let items: Vec<_> = merge_recent_tracks([("alice", alice), ("bob", bob)], false)
+        .collect()
+        .await;
+
+    assert_eq!(items.len(), 4);
+    assert_eq!(items.iter().filter(|v| v.is_err()).count(), 1);
+    assert_eq!(
+        items.last().unwrap().as_ref().unwrap().track.name.as_ref(),
+        "b2"
+    );
+}
+
+/// A page of extended recent tracks for `user`, newest first.
+async fn mount_history(server: &MockServer, user: &str, tracks: &[(&str, i64)]) {
+    let tracks = tracks
+        .iter()
+        .map(|(name, uts)| {
+            format!(
+                r##"{{"artist":{{"url":"https://www.last.fm/","name":"Artist","image":[],"mbid":""}},"album":{{"mbid":"","#text":""}},"name":"{name}","url":"https://www.last.fm/","image":[],"mbid":"","streamable":"0","loved":"0","date":{{"uts":"{uts}","#text":""}}}}"##
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    let body = format!(
+        r#"{{"recenttracks":{{"track":[{tracks}],"@attr":{{"user":"{user}","page":"1","perPage":"50","totalPages":"1","total":"2"}}}}}}"#
+    );
+
+    Mock::given(method("GET"))
+        .and(query_param("user", user))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(body, "application/json"))
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn walks_every_user_in_the_requested_direction() {
+    let server = MockServer::start().await;
+    mount_history(&server, "alice", &[("a3", 30), ("a1", 10)]).await;
+    mount_history(&server, "bob", &[("b4", 40), ("b2", 20)]).await;
+
+    // Asks for the first user only to be walked oldest first.
+    let configured = AtomicUsize::new(0);
+    let items: Vec<_> = common::public_client(&server)
+        .users_get_recent_tracks(&["alice", "bob"], false, |v| {
+            if configured.fetch_add(1, Ordering::Relaxed) == 0 {
+                v.oldest_first()
+            } else {
+                v
+            }
+        })
+        .await
+        .unwrap()
+        .map_ok(|v| (v.user.to_string(), v.track.name.to_string()))
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(
+        played(&items),
+        [
+            ("bob", "b4"),
+            ("alice", "a3"),
+            ("bob", "b2"),
+            ("alice", "a1"),
+        ]
+    );
+}
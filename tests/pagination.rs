@@ -0,0 +1,425 @@
+mod common;
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use futures::{StreamExt, TryStreamExt};
+use lastfm_rs_api::{
+    LastFm,
+    authentication::public::PublicAuthentication,
+    error::{Error, last_fm::LastFmError},
+    page::{
+        Paginated, PaginatedBuilder, PaginationConfig,
+        progress::{Progress, ProgressHook},
+    },
+};
+use reqwest::Method;
+use serde::Deserialize;
+use wiremock::{
+    Mock, MockServer, ResponseTemplate,
+    matchers::{method, query_param},
+};
+
+#[derive(Debug, Deserialize)]
+struct Item {
+    name: String,
+}
+
+fn page(page: u32, total_pages: u32, total: u32, names: &[&str]) -> String {
+    let items = names
+        .iter()
+        .map(|v| format!(r#"{{"name":"{v}"}}"#))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    format!(
+        r#"{{"things":{{"thing":[{items}],"@attr":{{"page":"{page}","perPage":"2","totalPages":"{total_pages}","total":"{total}"}}}}}}"#
+    )
+}
+
+async fn mount(server: &MockServer, number: u32, body: String) {
+    Mock::given(method("GET"))
+        .and(query_param("page", number.to_string()))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(body, "application/json"))
+        .mount(server)
+        .await;
+}
+
+async fn paginated(server: &MockServer, config: PaginationConfig) -> Paginated<Item> {
+    LastFm::new()
+        .with_base_url(&server.uri())
+        .request(Method::GET, "test.getthings")
+        .paginated::<Item>("things", "thing", config)
+        .await
+        .unwrap()
+}
+
+fn config() -> PaginationConfig {
+    PaginationConfig {
+        page_size: 2,
+        ..Default::default()
+    }
+}
+
+async fn names(pg: &mut Paginated<Item>) -> Vec<String> {
+    pg.send().map_ok(|v| v.name).try_collect().await.unwrap()
+}
+
+async fn requested_pages(server: &MockServer) -> Vec<String> {
+    common::query_values(server, "page").await
+}
+
+#[tokio::test]
+async fn stops_at_total_pages() {
+    let server = MockServer::start().await;
+    mount(&server, 1, page(1, 3, 5, &["a", "b"])).await;
+    mount(&server, 2, page(2, 3, 5, &["c", "d"])).await;
+    mount(&server, 3, page(3, 3, 5, &["e"])).await;
+
+    let mut pg = paginated(&server, config()).await;
+
+    assert_eq!(pg.total(), 5);
+    assert_eq!(pg.total_pages(), 3);
+    assert_eq!(names(&mut pg).await, ["a", "b", "c", "d", "e"]);
+    assert_eq!(requested_pages(&server).await, ["1", "2", "3"]);
+}
+
+#[tokio::test]
+async fn skips_keys_after_the_page() {
+    let server = MockServer::start().await;
+    let body = r#"{"things":{"thing":[{"name":"a"},{"name":"b"}],"@attr":{"page":"1","perPage":"2","totalPages":"1","total":"2"},"extra":1},"trailing":[2]}"#;
+    mount(&server, 1, body.to_owned()).await;
+
+    let mut pg = paginated(&server, config()).await;
+
+    assert_eq!(names(&mut pg).await, ["a", "b"]);
+}
+
+#[tokio::test]
+async fn empty_result_sends_one_request() {
+    let server = MockServer::start().await;
+    mount(&server, 1, page(1, 0, 0, &[])).await;
+
+    let mut pg = paginated(&server, config()).await;
+
+    assert!(names(&mut pg).await.is_empty());
+    assert_eq!(requested_pages(&server).await, ["1"]);
+}
+
+#[tokio::test]
+async fn empty_page_ends_the_walk() {
+    let server = MockServer::start().await;
+    mount(&server, 1, page(1, 3, 6, &["a", "b"])).await;
+    mount(&server, 2, page(2, 3, 6, &[])).await;
+    mount(&server, 3, page(3, 3, 6, &["e", "f"])).await;
+
+    let mut pg = paginated(&server, config()).await;
+
+    assert_eq!(names(&mut pg).await, ["a", "b"]);
+    assert_eq!(requested_pages(&server).await, ["1", "2"]);
+}
+
+#[tokio::test]
+async fn follows_a_shrinking_total() {
+    let server = MockServer::start().await;
+    mount(&server, 1, page(1, 3, 6, &["a", "b"])).await;
+    mount(&server, 2, page(2, 2, 4, &["c", "d"])).await;
+    mount(&server, 3, page(3, 3, 6, &["e", "f"])).await;
+
+    let mut pg = paginated(&server, config()).await;
+
+    assert_eq!(names(&mut pg).await, ["a", "b", "c", "d"]);
+    assert_eq!(requested_pages(&server).await, ["1", "2"]);
+}
+
+#[tokio::test]
+async fn follows_a_growing_total() {
+    let server = MockServer::start().await;
+    mount(&server, 1, page(1, 2, 4, &["a", "b"])).await;
+    mount(&server, 2, page(2, 3, 5, &["c", "d"])).await;
+    mount(&server, 3, page(3, 3, 5, &["e"])).await;
+
+    let mut pg = paginated(&server, config()).await;
+
+    assert_eq!(names(&mut pg).await, ["a", "b", "c", "d", "e"]);
+}
+
+#[tokio::test]
+async fn api_error_ends_the_walk() {
+    let server = MockServer::start().await;
+    mount(&server, 1, page(1, 3, 6, &["a", "b"])).await;
+    mount(
+        &server,
+        2,
+        r#"{"error":29,"message":"Rate limit exceeded"}"#.to_owned(),
+    )
+    .await;
+    mount(&server, 3, page(3, 3, 6, &["e", "f"])).await;
+
+    let mut pg = paginated(&server, config()).await;
+    let items: Vec<_> = pg.send().collect().await;
+
+    assert_eq!(items.len(), 3);
+    assert!(matches!(
+        items[2].as_ref().map_err(Error::inner),
+        Err(Error::ApiError(LastFmError::RateLimitExceeded { .. }))
+    ));
+    assert_eq!(requested_pages(&server).await, ["1", "2"]);
+}
+
+#[tokio::test]
+async fn max_items_limits_requests() {
+    let server = MockServer::start().await;
+    mount(&server, 1, page(1, 3, 6, &["a", "b"])).await;
+    mount(&server, 2, page(2, 3, 6, &["c", "d"])).await;
+    mount(&server, 3, page(3, 3, 6, &["e", "f"])).await;
+
+    let config = PaginationConfig {
+        max_items: Some(3),
+        prefetch: 4,
+        ..config()
+    };
+    let mut pg = paginated(&server, config).await;
+
+    assert_eq!(names(&mut pg).await, ["a", "b", "c"]);
+    assert_eq!(requested_pages(&server).await, ["1", "2"]);
+}
+
+#[tokio::test]
+async fn prefetching_keeps_order() {
+    let server = MockServer::start().await;
+    mount(&server, 1, page(1, 4, 8, &["a", "b"])).await;
+    mount(&server, 2, page(2, 4, 8, &["c", "d"])).await;
+    mount(&server, 3, page(3, 4, 8, &["e", "f"])).await;
+    mount(&server, 4, page(4, 4, 8, &["g", "h"])).await;
+
+    let config = PaginationConfig {
+        prefetch: 3,
+        ..config()
+    };
+    let mut pg = paginated(&server, config).await;
+
+    assert_eq!(
+        names(&mut pg).await,
+        ["a", "b", "c", "d", "e", "f", "g", "h"]
+    );
+}
+
+#[tokio::test]
+async fn reverse_walks_from_the_last_page() {
+    let server = MockServer::start().await;
+    mount(&server, 1, page(1, 3, 5, &["a", "b"])).await;
+    mount(&server, 2, page(2, 3, 5, &["c", "d"])).await;
+    mount(&server, 3, page(3, 3, 5, &["e"])).await;
+
+    let config = PaginationConfig {
+        reverse: true,
+        ..config()
+    };
+    let mut pg = paginated(&server, config).await;
+
+    assert_eq!(names(&mut pg).await, ["e", "d", "c", "b", "a"]);
+    assert_eq!(requested_pages(&server).await, ["1", "3", "2"]);
+}
+
+#[tokio::test]
+async fn finished_reverse_walks_resume_as_finished() {
+    let server = MockServer::start().await;
+    mount(&server, 1, page(1, 2, 3, &["a", "b"])).await;
+    mount(&server, 2, page(2, 2, 3, &["c"])).await;
+
+    let config = PaginationConfig {
+        reverse: true,
+        ..config()
+    };
+    let mut pg = paginated(&server, config).await;
+    let mut stream = pg.send();
+    while stream.next().await.is_some() {}
+
+    let cursor = stream.cursor().clone();
+    assert_eq!((cursor.page, cursor.offset), (0, 0));
+
+    let mut resumed = LastFm::new()
+        .with_base_url(&server.uri())
+        .with_authentication(PublicAuthentication::new("key"))
+        .resume::<Item>(&cursor)
+        .await
+        .unwrap();
+
+    assert!(names(&mut resumed).await.is_empty());
+    assert_eq!(requested_pages(&server).await, ["1", "2", "1"]);
+}
+
+#[tokio::test]
+async fn resumes_from_a_cursor() {
+    let server = MockServer::start().await;
+    mount(&server, 1, page(1, 3, 5, &["a", "b"])).await;
+    mount(&server, 2, page(2, 3, 5, &["c", "d"])).await;
+    mount(&server, 3, page(3, 3, 5, &["e"])).await;
+
+    let mut pg = paginated(&server, config()).await;
+    let mut stream = pg.send();
+    for _ in 0..3 {
+        stream.next().await.unwrap().unwrap();
+    }
+
+    let cursor = stream.cursor().clone();
+    assert_eq!((cursor.page, cursor.offset), (2, 1));
+
+    let mut resumed = LastFm::new()
+        .with_base_url(&server.uri())
+        .with_authentication(PublicAuthentication::new("key"))
+        .resume::<Item>(&cursor)
+        .await
+        .unwrap();
+
+    assert_eq!(names(&mut resumed).await, ["d", "e"]);
+}
+
+#[tokio::test]
+async fn rejects_oversized_pages() {
+    let server = MockServer::start().await;
+
+    for method in ["user.getrecenttracks", "user.getRecentTracks"] {
+        let result = LastFm::new()
+            .with_base_url(&server.uri())
+            .request(Method::GET, method)
+            .paginated::<Item>(
+                "recenttracks",
+                "track",
+                PaginationConfig {
+                    page_size: 500,
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        assert!(
+            matches!(result, Err(Error::InvalidPageSize { max: 200, .. })),
+            "{method}"
+        );
+    }
+    assert!(requested_pages(&server).await.is_empty());
+}
+
+#[tokio::test]
+async fn reports_progress_per_page() {
+    let server = MockServer::start().await;
+    mount(&server, 1, page(1, 3, 5, &["a", "b"])).await;
+    mount(&server, 2, page(2, 3, 5, &["c", "d"])).await;
+    mount(&server, 3, page(3, 3, 5, &["e"])).await;
+
+    let reports = Arc::new(Mutex::new(Vec::<Progress>::new()));
+    let sink = reports.clone();
+    let config = PaginationConfig {
+        progress: Some(ProgressHook::new(move |v| sink.lock().unwrap().push(v))),
+        ..config()
+    };
+    let mut pg = paginated(&server, config).await;
+    names(&mut pg).await;
+
+    let reports = reports.lock().unwrap();
+    let counts: Vec<_> = reports
+        .iter()
+        .map(|v| (v.pages_fetched, v.items_yielded))
+        .collect();
+
+    assert_eq!(counts, [(1, 2), (2, 4), (3, 5)]);
+    assert!(
+        reports
+            .iter()
+            .all(|v| v.total_items == 5 && v.total_pages == 3)
+    );
+    assert!(reports[0].eta.is_none());
+    assert_eq!(reports[2].eta, Some(Duration::ZERO));
+}
+
+async fn walk(server: &MockServer, config: PaginationConfig) -> (Vec<String>, Vec<String>) {
+    let mut pg = paginated(server, config).await;
+    let names = names(&mut pg).await;
+
+    (names, requested_pages(server).await)
+}
+
+async fn three_pages() -> MockServer {
+    let server = MockServer::start().await;
+    mount(&server, 1, page(1, 3, 5, &["a", "b"])).await;
+    mount(&server, 2, page(2, 3, 5, &["c", "d"])).await;
+    mount(&server, 3, page(3, 3, 5, &["e"])).await;
+    server
+}
+
+#[tokio::test]
+async fn starts_at_the_start_page() {
+    let server = three_pages().await;
+    let config = PaginationConfig {
+        start_page: Some(2),
+        ..config()
+    };
+
+    let (names, pages) = walk(&server, config).await;
+    assert_eq!(names, ["c", "d", "e"]);
+    assert_eq!(pages, ["2", "3"]);
+}
+
+#[tokio::test]
+async fn start_page_zero_starts_at_the_first_page() {
+    let server = three_pages().await;
+    let config = PaginationConfig {
+        start_page: Some(0),
+        ..config()
+    };
+
+    let (names, pages) = walk(&server, config).await;
+    assert_eq!(names, ["a", "b", "c", "d", "e"]);
+    assert_eq!(pages, ["1", "2", "3"]);
+}
+
+#[tokio::test]
+async fn reverse_starts_at_the_start_page() {
+    let server = three_pages().await;
+    let config = PaginationConfig {
+        start_page: Some(2),
+        reverse: true,
+        ..config()
+    };
+
+    let (names, pages) = walk(&server, config).await;
+    assert_eq!(names, ["d", "c", "b", "a"]);
+    assert_eq!(pages, ["2", "1"]);
+}
+
+#[tokio::test]
+async fn max_pages_limits_requests() {
+    let server = three_pages().await;
+    let config = PaginationConfig {
+        max_pages: Some(2),
+        ..config()
+    };
+
+    let (names, pages) = walk(&server, config).await;
+    assert_eq!(names, ["a", "b", "c", "d"]);
+    assert_eq!(pages, ["1", "2"]);
+}
+
+#[tokio::test]
+async fn reverse_max_pages_counts_finding_the_last_page() {
+    for (max_pages, expected_names, expected_pages) in [
+        (1, &[][..], &["1"][..]),
+        (2, &["e"][..], &["1", "3"][..]),
+        (3, &["e", "d", "c", "b", "a"][..], &["1", "3", "2"][..]),
+    ] {
+        let server = three_pages().await;
+        let config = PaginationConfig {
+            max_pages: Some(max_pages),
+            reverse: true,
+            ..config()
+        };
+
+        let (names, pages) = walk(&server, config).await;
+        assert_eq!(names, expected_names, "max_pages {max_pages}");
+        assert_eq!(pages, expected_pages, "max_pages {max_pages}");
+    }
+}
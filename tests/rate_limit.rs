@@ -0,0 +1,281 @@
+mod common;
+
+use std::time::{Duration, Instant};
+
+use futures::{TryStreamExt, future::join_all};
+use lastfm_rs_api::{
+    LastFm,
+    page::{PaginatedBuilder, PaginationConfig},
+    rate_limit::{Priority, RateLimit},
+};
+use reqwest::Method;
+use serde_json::{Value, json};
+use wiremock::{
+    Mock, MockServer, ResponseTemplate,
+    matchers::{method, query_param},
+};
+
+async fn server() -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+        .mount(&server)
+        .await;
+    server
+}
+
+/// Lets every spawned call run until it waits on the rate limit. Tests run
+/// on a single thread, so the spawned calls all go before this one resumes.
+async fn queue_spawned_calls() {
+    tokio::task::yield_now().await;
+}
+
+fn limit() -> RateLimit {
+    RateLimit {
+        requests: 2,
+        per: Duration::from_millis(200),
+    }
+}
+
+#[tokio::test]
+async fn spaces_out_requests_past_the_burst() {
+    let server = server().await;
+    let mut client = LastFm::new()
+        .with_base_url(&server.uri())
+        .with_rate_limit(limit());
+
+    let started = Instant::now();
+    for _ in 0..6 {
+        client
+            .request(Method::GET, "test.get")
+            .send()
+            .await
+            .unwrap();
+    }
+
+    assert!(started.elapsed() >= Duration::from_millis(390));
+}
+
+#[tokio::test]
+async fn is_shared_across_clones() {
+    let server = server().await;
+    let client = LastFm::new()
+        .with_base_url(&server.uri())
+        .with_rate_limit(limit());
+
+    let started = Instant::now();
+    join_all((0..6).map(|_| {
+        let mut client = client.clone();
+        async move {
+            client
+                .request(Method::GET, "test.get")
+                .send()
+                .await
+                .unwrap()
+        }
+    }))
+    .await;
+
+    assert!(started.elapsed() >= Duration::from_millis(390));
+}
+
+#[tokio::test]
+async fn can_be_turned_off() {
+    let server = server().await;
+    let mut client = LastFm::new()
+        .with_base_url(&server.uri())
+        .with_rate_limit(RateLimit {
+            requests: 1,
+            per: Duration::from_secs(60),
+        })
+        .without_rate_limit();
+
+    let started = Instant::now();
+    for _ in 0..3 {
+        client
+            .request(Method::GET, "test.get")
+            .send()
+            .await
+            .unwrap();
+    }
+
+    assert!(started.elapsed() < Duration::from_secs(30));
+}
+
+#[tokio::test]
+async fn interactive_calls_go_ahead_of_queued_background_ones() {
+    let server = server().await;
+    let client = LastFm::new()
+        .with_base_url(&server.uri())
+        .with_rate_limit(RateLimit {
+            requests: 1,
+            per: Duration::from_millis(200),
+        });
+
+    let exports = (0..4)
+        .map(|_| {
+            let mut client = client.clone().with_priority(Priority::Background);
+            tokio::spawn(async move {
+                client
+                    .request(Method::GET, "export.get")
+                    .send()
+                    .await
+                    .unwrap()
+            })
+        })
+        .collect::<Vec<_>>();
+
+    queue_spawned_calls().await;
+    client
+        .clone()
+        .request(Method::GET, "lookup.get")
+        .send()
+        .await
+        .unwrap();
+
+    join_all(exports).await;
+
+    assert_eq!(
+        common::query_values(&server, "method").await,
+        [
+            "export.get",
+            "lookup.get",
+            "export.get",
+            "export.get",
+            "export.get"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn interactive_calls_go_ahead_of_page_fetches() {
+    let server = MockServer::start().await;
+    for page in 1..=3 {
+        Mock::given(query_param("page", page.to_string()))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "things": {
+                    "thing": [{ "name": page.to_string() }],
+                    "@attr": {
+                        "page": page.to_string(),
+                        "perPage": "1",
+                        "totalPages": "3",
+                        "total": "3"
+                    }
+                }
+            })))
+            .mount(&server)
+            .await;
+    }
+    Mock::given(query_param("method", "lookup.get"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+        .mount(&server)
+        .await;
+
+    let client = LastFm::new()
+        .with_base_url(&server.uri())
+        .with_rate_limit(RateLimit {
+            requests: 1,
+            per: Duration::from_millis(200),
+        });
+
+    let mut walk = client
+        .clone()
+        .request(Method::GET, "export.get")
+        .paginated::<Value>("things", "thing", PaginationConfig::default())
+        .await
+        .unwrap();
+    let export = tokio::spawn(async move { walk.send().try_collect::<Vec<_>>().await });
+
+    queue_spawned_calls().await;
+    client
+        .clone()
+        .request(Method::GET, "lookup.get")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(export.await.unwrap().unwrap().len(), 3);
+    assert_eq!(
+        common::query_values(&server, "method").await,
+        ["export.get", "lookup.get", "export.get", "export.get"]
+    );
+}
+
+#[tokio::test]
+async fn calls_that_stop_waiting_leave_the_queue() {
+    let server = server().await;
+    let mut client = LastFm::new()
+        .with_base_url(&server.uri())
+        .with_rate_limit(RateLimit {
+            requests: 1,
+            per: Duration::from_millis(200),
+        });
+
+    client
+        .request(Method::GET, "test.get")
+        .send()
+        .await
+        .unwrap();
+
+    let gave_up = tokio::time::timeout(
+        Duration::from_millis(20),
+        client.request(Method::GET, "test.get").send(),
+    )
+    .await;
+    assert!(gave_up.is_err());
+
+    let started = Instant::now();
+    client
+        .request(Method::GET, "test.get")
+        .with_priority(Priority::Background)
+        .send()
+        .await
+        .unwrap();
+
+    assert!(started.elapsed() < Duration::from_millis(300));
+}
+
+#[tokio::test]
+async fn calls_that_stop_waiting_leave_enough_tokens_for_the_rest() {
+    let server = server().await;
+    let client = LastFm::new().with_base_url(&server.uri());
+
+    for _ in 0..5 {
+        client
+            .clone()
+            .request(Method::GET, "test.get")
+            .send()
+            .await
+            .unwrap();
+    }
+
+    let waiting = (0..4)
+        .map(|_| {
+            let mut client = client.clone();
+            tokio::spawn(async move {
+                client
+                    .request(Method::GET, "test.get")
+                    .send()
+                    .await
+                    .unwrap()
+            })
+        })
+        .collect::<Vec<_>>();
+
+    queue_spawned_calls().await;
+    for call in &waiting[..3] {
+        call.abort();
+    }
+
+    // At least enough tokens for the call left waiting and this one.
+    tokio::time::sleep(Duration::from_millis(650)).await;
+    client
+        .clone()
+        .request(Method::GET, "test.get")
+        .send()
+        .await
+        .unwrap();
+
+    waiting.into_iter().last().unwrap().await.unwrap();
+    assert_eq!(common::requests(&server).await, 7);
+}
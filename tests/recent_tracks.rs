@@ -0,0 +1,303 @@
+mod common;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use futures::TryStreamExt;
+use lastfm_rs_api::{page::PaginationConfig, types::track::BasicTrack};
+use wiremock::{
+    Mock, MockServer, ResponseTemplate,
+    matchers::{method, query_param},
+};
+
+use common::{public_client, recent_track};
+
+fn page(page: u32, per_page: u32, total_pages: u32, tracks: &[String]) -> ResponseTemplate {
+    let total = per_page * total_pages;
+
+    ResponseTemplate::new(200).set_body_raw(
+        format!(
+            r#"{{"recenttracks":{{"track":[{}],"@attr":{{"user":"rj","page":"{page}","perPage":"{per_page}","totalPages":"{total_pages}","total":"{total}"}}}}}}"#,
+            tracks.join(",")
+        ),
+        "application/json",
+    )
+}
+
+async fn mount(server: &MockServer, limit: u32, number: u32, template: ResponseTemplate) {
+    Mock::given(method("GET"))
+        .and(query_param("limit", limit.to_string()))
+        .and(query_param("page", number.to_string()))
+        .respond_with(template)
+        .mount(server)
+        .await;
+}
+
+/// Walks the recent tracks of "rj" newest first, two to a page.
+async fn names(server: &MockServer) -> Vec<String> {
+    public_client(server)
+        .user_get_recent_tracks("rj")
+        .with_config(PaginationConfig {
+            page_size: 2,
+            ..Default::default()
+        })
+        .fetch_basic()
+        .await
+        .unwrap()
+        .map_ok(|v| v.name.to_string())
+        .try_collect()
+        .await
+        .unwrap()
+}
+
+/// The `to` of every request for a page of `limit` items.
+async fn pinned_to(server: &MockServer, limit: &str) -> Vec<Option<String>> {
+    server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .filter(|v| v.url.query_pairs().any(|(k, v)| k == "limit" && v == limit))
+        .map(|v| {
+            v.url
+                .query_pairs()
+                .find(|(k, _)| k == "to")
+                .map(|(_, v)| v.into_owned())
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn pins_to_just_past_the_newest_scrobble() {
+    let server = MockServer::start().await;
+    mount(
+        &server,
+        1,
+        1,
+        page(
+            1,
+            1,
+            3,
+            &[recent_track("np", None), recent_track("c", Some(300))],
+        ),
+    )
+    .await;
+    mount(
+        &server,
+        2,
+        1,
+        page(
+            1,
+            2,
+            2,
+            &[recent_track("c", Some(300)), recent_track("b", Some(200))],
+        ),
+    )
+    .await;
+    mount(
+        &server,
+        2,
+        2,
+        page(2, 2, 2, &[recent_track("a", Some(100))]),
+    )
+    .await;
+
+    assert_eq!(names(&server).await, ["c", "b", "a"]);
+    assert_eq!(pinned_to(&server, "1").await, [None]);
+    assert_eq!(
+        pinned_to(&server, "2").await,
+        [Some("301".to_owned()), Some("301".to_owned())]
+    );
+}
+
+#[tokio::test]
+async fn yields_a_scrobble_pushed_across_a_page_boundary_once() {
+    let server = MockServer::start().await;
+    mount(
+        &server,
+        1,
+        1,
+        page(1, 1, 3, &[recent_track("c", Some(300))]),
+    )
+    .await;
+    mount(
+        &server,
+        2,
+        1,
+        page(
+            1,
+            2,
+            2,
+            &[recent_track("c", Some(300)), recent_track("b", Some(200))],
+        ),
+    )
+    .await;
+    // A backdated scrobble pushed "b" onto the second page as well.
+    mount(
+        &server,
+        2,
+        2,
+        page(
+            2,
+            2,
+            2,
+            &[recent_track("b", Some(200)), recent_track("a", Some(100))],
+        ),
+    )
+    .await;
+
+    assert_eq!(names(&server).await, ["c", "b", "a"]);
+}
+
+fn at(uts: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp(uts, 0).unwrap()
+}
+
+/// Answers the window starting a second before `from` with `tracks`.
+async fn window(server: &MockServer, from: i64, tracks: &[String]) {
+    Mock::given(method("GET"))
+        .and(query_param("from", (from - 1).to_string()))
+        .respond_with(page(1, 50, 1, tracks))
+        .mount(server)
+        .await;
+}
+
+/// Three 100 second windows from 1000 to 1300, with "b" on the boundary of
+/// the first two.
+async fn windows(server: &MockServer) {
+    window(
+        server,
+        1000,
+        &[recent_track("b", Some(1100)), recent_track("a", Some(1000))],
+    )
+    .await;
+    window(
+        server,
+        1100,
+        &[recent_track("c", Some(1150)), recent_track("b", Some(1100))],
+    )
+    .await;
+    window(server, 1200, &[recent_track("d", Some(1250))]).await;
+}
+
+async fn sharded_with(
+    server: &MockServer,
+    from: Option<i64>,
+    oldest_first: bool,
+    config: PaginationConfig,
+) -> Vec<String> {
+    let mut recent = public_client(server)
+        .user_get_recent_tracks("rj")
+        .with_config(config)
+        .with_end_date(at(1300));
+
+    if let Some(from) = from {
+        recent = recent.with_start_date(at(from));
+    }
+
+    if oldest_first {
+        recent = recent.oldest_first();
+    }
+
+    recent
+        .fetch_sharded::<BasicTrack>(TimeDelta::seconds(100), 2)
+        .await
+        .unwrap()
+        .map_ok(|v| v.name.to_string())
+        .try_collect()
+        .await
+        .unwrap()
+}
+
+async fn sharded(server: &MockServer, from: Option<i64>, oldest_first: bool) -> Vec<String> {
+    sharded_with(server, from, oldest_first, PaginationConfig::default()).await
+}
+
+#[tokio::test]
+async fn sharded_walks_windows_newest_first() {
+    let server = MockServer::start().await;
+    windows(&server).await;
+
+    assert_eq!(
+        sharded(&server, Some(1000), false).await,
+        ["d", "c", "b", "a"]
+    );
+
+    let mut ranges = common::query_values(&server, "from").await;
+    ranges.sort();
+    assert_eq!(ranges, ["1099", "1199", "999"]);
+    assert_eq!(common::query_values(&server, "to").await.len(), 3);
+}
+
+#[tokio::test]
+async fn sharded_walks_windows_oldest_first() {
+    let server = MockServer::start().await;
+    windows(&server).await;
+
+    assert_eq!(
+        sharded(&server, Some(1000), true).await,
+        ["a", "b", "c", "d"]
+    );
+}
+
+#[tokio::test]
+async fn sharded_starts_at_the_oldest_scrobble_without_a_start_date() {
+    let server = MockServer::start().await;
+    windows(&server).await;
+    mount(
+        &server,
+        200,
+        1,
+        page(
+            1,
+            200,
+            1,
+            &[recent_track("b", Some(1100)), recent_track("a", Some(1000))],
+        ),
+    )
+    .await;
+
+    assert_eq!(sharded(&server, None, false).await, ["d", "c", "b", "a"]);
+    assert_eq!(common::query_values(&server, "limit").await[0], "200");
+}
+
+#[tokio::test]
+async fn sharded_limits_the_merged_stream() {
+    let server = MockServer::start().await;
+    windows(&server).await;
+
+    let config = PaginationConfig {
+        start_page: Some(2),
+        max_pages: Some(1),
+        max_items: Some(3),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        sharded_with(&server, Some(1000), false, config).await,
+        ["d", "c", "b"]
+    );
+    assert!(
+        common::query_values(&server, "page")
+            .await
+            .iter()
+            .all(|v| v == "1")
+    );
+}
+
+#[tokio::test]
+async fn extended_tracks_need_a_loved_status() {
+    let server = MockServer::start().await;
+    let unloved = r##"{"artist":{"url":"https://www.last.fm/music/Cher","name":"Cher","image":[],"mbid":""},"album":{"mbid":"","#text":""},"name":"Believe","url":"https://www.last.fm/","image":[],"mbid":"","streamable":"0","date":{"uts":"100","#text":""}}"##;
+    common::serve(&server, page(1, 50, 1, &[unloved.to_owned()]), 1).await;
+
+    let result = public_client(&server)
+        .user_get_recent_tracks("rj")
+        .with_end_date(at(1300))
+        .fetch()
+        .await;
+    let error = match result {
+        Ok(stream) => stream.try_collect::<Vec<_>>().await.unwrap_err(),
+        Err(error) => error,
+    };
+
+    assert!(error.to_string().contains("loved"), "{error}");
+}
@@ -0,0 +1,209 @@
+mod common;
+
+use std::time::Duration;
+
+use futures::TryStreamExt;
+use lastfm_rs_api::{
+    LastFm,
+    error::{Error, last_fm::LastFmError},
+    page::{PaginatedBuilder, PaginationConfig},
+    retry::RetryPolicy,
+};
+use reqwest::Method;
+use serde::Deserialize;
+use serde_json::Value;
+use wiremock::{
+    Mock, MockServer, ResponseTemplate,
+    matchers::{method, query_param},
+};
+
+use common::{api_error, ok, requests};
+
+fn policy() -> RetryPolicy {
+    RetryPolicy {
+        initial_backoff: Duration::from_millis(1),
+        ..Default::default()
+    }
+}
+
+fn client(server: &MockServer) -> LastFm<()> {
+    common::client(server).with_retry(policy())
+}
+
+async fn fails_then_succeeds(server: &MockServer, http: &str, failure: ResponseTemplate) {
+    Mock::given(method(http))
+        .respond_with(failure)
+        .up_to_n_times(1)
+        .mount(server)
+        .await;
+    Mock::given(method(http))
+        .respond_with(ok())
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn retries_temporary_errors() {
+    let server = MockServer::start().await;
+    fails_then_succeeds(&server, "GET", api_error(16)).await;
+
+    let result = client(&server)
+        .request(Method::GET, "test.get")
+        .fetch::<Value>()
+        .await;
+
+    assert!(result.is_ok());
+    assert_eq!(requests(&server).await, 2);
+}
+
+#[tokio::test]
+async fn retries_server_errors() {
+    let server = MockServer::start().await;
+    fails_then_succeeds(&server, "GET", ResponseTemplate::new(503)).await;
+
+    let result = client(&server)
+        .request(Method::GET, "test.get")
+        .fetch::<Value>()
+        .await;
+
+    assert!(result.is_ok());
+    assert_eq!(requests(&server).await, 2);
+}
+
+#[tokio::test]
+async fn gives_up_after_max_retries() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(api_error(29))
+        .mount(&server)
+        .await;
+
+    let result = client(&server)
+        .request(Method::GET, "test.get")
+        .fetch::<Value>()
+        .await;
+
+    assert!(matches!(
+        result.as_ref().map_err(Error::inner),
+        Err(Error::ApiError(LastFmError::RateLimitExceeded { .. }))
+    ));
+    assert_eq!(requests(&server).await, 4);
+}
+
+#[tokio::test]
+async fn does_not_retry_client_errors() {
+    let server = MockServer::start().await;
+    fails_then_succeeds(&server, "GET", api_error(6)).await;
+
+    let result = client(&server)
+        .request(Method::GET, "test.get")
+        .fetch::<Value>()
+        .await;
+
+    assert!(matches!(
+        result.as_ref().map_err(Error::inner),
+        Err(Error::ApiError(LastFmError::InvalidParameters { .. }))
+    ));
+    assert_eq!(requests(&server).await, 1);
+}
+
+#[tokio::test]
+async fn does_not_retry_writes_unless_asked() {
+    let server = MockServer::start().await;
+    fails_then_succeeds(&server, "POST", api_error(16)).await;
+
+    let result = client(&server)
+        .request(Method::POST, "test.post")
+        .fetch::<Value>()
+        .await;
+
+    assert!(result.is_err());
+    assert_eq!(requests(&server).await, 1);
+
+    let result = client(&server)
+        .with_retry(RetryPolicy {
+            retry_writes: true,
+            ..policy()
+        })
+        .request(Method::POST, "test.post")
+        .fetch::<Value>()
+        .await;
+
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn does_not_retry_without_a_policy() {
+    let server = MockServer::start().await;
+    fails_then_succeeds(&server, "GET", api_error(16)).await;
+
+    let result = LastFm::new()
+        .with_base_url(&server.uri())
+        .request(Method::GET, "test.get")
+        .fetch::<Value>()
+        .await;
+
+    assert!(result.is_err());
+    assert_eq!(requests(&server).await, 1);
+}
+
+#[derive(Debug, Deserialize)]
+struct Item {
+    name: String,
+}
+
+fn page(page: u32, names: &[&str]) -> ResponseTemplate {
+    let items = names
+        .iter()
+        .map(|v| format!(r#"{{"name":"{v}"}}"#))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    ResponseTemplate::new(200).set_body_raw(
+        format!(
+            r#"{{"things":{{"thing":[{items}],"@attr":{{"page":"{page}","perPage":"2","totalPages":"3","total":"6"}}}}}}"#
+        ),
+        "application/json",
+    )
+}
+
+#[tokio::test]
+async fn retries_only_the_failing_page() {
+    let server = MockServer::start().await;
+    Mock::given(query_param("page", "2"))
+        .respond_with(api_error(8))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    for (number, names) in [(1, ["a", "b"]), (2, ["c", "d"]), (3, ["e", "f"])] {
+        Mock::given(query_param("page", number.to_string()))
+            .respond_with(page(number, &names))
+            .mount(&server)
+            .await;
+    }
+
+    let items: Vec<String> = client(&server)
+        .request(Method::GET, "test.getthings")
+        .paginated::<Item>(
+            "things",
+            "thing",
+            PaginationConfig {
+                page_size: 2,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap()
+        .send()
+        .map_ok(|v| v.name)
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(items, ["a", "b", "c", "d", "e", "f"]);
+
+    assert_eq!(
+        common::query_values(&server, "page").await,
+        ["1", "2", "2", "3"]
+    );
+}
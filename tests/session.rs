@@ -0,0 +1,101 @@
+mod common;
+
+use lastfm_rs_api::{LastFm, authentication::session::SessionAuthentication};
+use reqwest::Method;
+use serde_json::json;
+use wiremock::{MockServer, ResponseTemplate};
+
+use common::{ok, serve};
+
+const SECRET: &str = "secret";
+
+fn client(server: &MockServer) -> LastFm<SessionAuthentication> {
+    common::client(server)
+        .with_authentication(SessionAuthentication::new("key", SECRET, "session", "rj"))
+}
+
+/// The query of the only request the server got.
+async fn query(server: &MockServer) -> Vec<(String, String)> {
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 1);
+
+    requests[0]
+        .url
+        .query_pairs()
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect()
+}
+
+#[tokio::test]
+async fn signs_the_sorted_parameters_without_format() {
+    let server = MockServer::start().await;
+    serve(&server, ok(), 1).await;
+
+    client(&server)
+        .request(Method::GET, "track.love")
+        .query(&[("track", "Believe"), ("artist", "Cher")])
+        .send()
+        .await
+        .unwrap();
+
+    let mut query = query(&server).await;
+    let signature = query
+        .iter()
+        .position(|(k, _)| k == "api_sig")
+        .map(|i| query.remove(i).1)
+        .unwrap();
+
+    let mut signed = query
+        .into_iter()
+        .filter(|(k, _)| k != "format")
+        .collect::<Vec<_>>();
+    signed.sort();
+
+    let raw = signed
+        .iter()
+        .map(|(k, v)| format!("{k}{v}"))
+        .chain([SECRET.to_owned()])
+        .collect::<String>();
+
+    assert_eq!(
+        signed.iter().map(|(k, _)| k.as_str()).collect::<Vec<_>>(),
+        ["api_key", "artist", "method", "sk", "track"]
+    );
+    assert_eq!(signature, format!("{:x}", md5::compute(raw)));
+}
+
+#[tokio::test]
+async fn user_get_self_asks_for_the_session_user() {
+    let server = MockServer::start().await;
+    let user = ResponseTemplate::new(200).set_body_json(json!({
+        "user": {
+            "name": "RJ",
+            "age": "0",
+            "subscriber": "1",
+            "realname": "Richard Jones",
+            "bootstrap": "0",
+            "playcount": "150316",
+            "artist_count": "12749",
+            "playlists": "0",
+            "track_count": "57436",
+            "album_count": "26085",
+            "image": [{ "size": "small", "#text": "" }],
+            "country": "United Kingdom",
+            "gender": "n",
+            "url": "https://www.last.fm/user/RJ",
+            "kind": "alum",
+            "registered": { "unixtime": 1037793040, "#text": 1037793040 },
+        }
+    }));
+    serve(&server, user, 1).await;
+
+    let me = client(&server).user_get_self().await.unwrap();
+
+    assert_eq!(&*me.username, "rj");
+    assert_eq!(&*me.user.name, "RJ");
+    assert_eq!(me.user.playcount, 150316);
+
+    let query = query(&server).await;
+    assert!(query.contains(&("method".to_owned(), "user.getinfo".to_owned())));
+    assert!(query.iter().all(|(k, _)| k != "user"));
+}
@@ -0,0 +1,165 @@
+mod common;
+
+use futures::TryStreamExt;
+use lastfm_rs_api::{
+    error::Error,
+    request::lookup::{ArtistLookup, TrackLookup},
+    types::tag::{PersonalTagging, TaggingType},
+};
+use serde_json::{Value, json};
+use wiremock::{MockServer, ResponseTemplate};
+
+use common::{public_client, query_values, serve};
+
+fn taggings(kind: &str, items: Value) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(json!({
+        "taggings": {
+            format!("{kind}s"): { kind: items },
+            "@attr": { "page": "1", "perPage": "50", "totalPages": "1", "total": "1" },
+        }
+    }))
+}
+
+async fn personal_tags(
+    server: &MockServer,
+    tagging_type: TaggingType,
+) -> Result<Vec<PersonalTagging>, Error> {
+    public_client(server)
+        .user_get_personal_tags("rj", "rock", tagging_type)
+        .await?
+        .send()
+        .try_collect()
+        .await
+}
+
+#[tokio::test]
+async fn decodes_top_tags() {
+    let server = MockServer::start().await;
+    let body = json!({ "toptags": { "tag": { "name": "rock", "count": "12" } } });
+    serve(&server, ResponseTemplate::new(200).set_body_json(body), 1).await;
+
+    let tags = public_client(&server)
+        .user_get_top_tags("rj")
+        .await
+        .unwrap();
+
+    assert_eq!(tags.len(), 1);
+    assert_eq!(&*tags[0].name, "rock");
+    assert_eq!(tags[0].count, 12);
+}
+
+#[tokio::test]
+async fn decodes_personal_tags_as_the_requested_type() {
+    let server = MockServer::start().await;
+    let artist = json!({ "name": "Cher", "mbid": "" });
+    serve(&server, taggings("artist", json!([artist])), 1).await;
+    serve(
+        &server,
+        taggings("album", json!({ "name": "Believe", "artist": artist })),
+        1,
+    )
+    .await;
+    serve(
+        &server,
+        taggings(
+            "track",
+            json!([{ "name": "Believe", "artist": artist, "duration": "240" }]),
+        ),
+        1,
+    )
+    .await;
+
+    let artists = personal_tags(&server, TaggingType::Artist).await.unwrap();
+    assert!(matches!(&artists[..], [PersonalTagging::Artist(v)] if v.musicbrainz_id.is_none()));
+
+    let albums = personal_tags(&server, TaggingType::Album).await.unwrap();
+    assert!(matches!(&albums[..], [PersonalTagging::Album(v)] if &*v.artist.name == "Cher"));
+
+    let tracks = personal_tags(&server, TaggingType::Track).await.unwrap();
+    assert!(matches!(&tracks[..], [PersonalTagging::Track(v)] if v.duration == 240));
+}
+
+#[tokio::test]
+async fn malformed_tracks_fail_rather_than_decode_as_albums() {
+    let server = MockServer::start().await;
+    let item = json!({ "name": "Believe", "artist": { "name": "Cher" }, "duration": "FIXME" });
+    serve(&server, taggings("track", json!([item])), 1).await;
+
+    let error = personal_tags(&server, TaggingType::Track)
+        .await
+        .unwrap_err();
+
+    let Error::ParseError(e) = error.inner() else {
+        panic!("{error}");
+    };
+    assert_eq!(e.path, "taggings.tracks.track[0].duration");
+}
+
+#[tokio::test]
+async fn looks_up_top_tags_by_musicbrainz_id() {
+    let server = MockServer::start().await;
+    let body = json!({
+        "toptags": {
+            "tag": [{ "name": "pop", "count": 100 }, { "name": "dance", "count": "40" }],
+            "@attr": { "artist": "Cher" },
+        }
+    });
+    serve(&server, ResponseTemplate::new(200).set_body_json(body), 1).await;
+
+    let mbid = "bfcc6d75-a6a5-4bc6-8282-47aec8531818";
+    let tags = public_client(&server)
+        .artist_get_top_tags(ArtistLookup::MusicBrainzId(mbid))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        tags.iter().map(|v| (&*v.name, v.count)).collect::<Vec<_>>(),
+        [("pop", 100), ("dance", 40)]
+    );
+    assert_eq!(query_values(&server, "method").await, ["artist.gettoptags"]);
+    assert_eq!(query_values(&server, "mbid").await, [mbid]);
+    assert!(query_values(&server, "artist").await.is_empty());
+}
+
+#[tokio::test]
+async fn gets_the_tags_a_user_applied() {
+    let server = MockServer::start().await;
+    let body = json!({
+        "tags": {
+            "tag": { "name": "classic", "url": "https://www.last.fm/tag/classic" },
+            "@attr": { "artist": "Cher", "track": "Believe" },
+        }
+    });
+    serve(&server, ResponseTemplate::new(200).set_body_json(body), 1).await;
+
+    let track = TrackLookup::Name {
+        artist: "Cher",
+        track: "Believe",
+    };
+    let tags = public_client(&server)
+        .track_get_tags(track, "rj")
+        .await
+        .unwrap();
+
+    assert_eq!(tags.len(), 1);
+    assert_eq!(&*tags[0].name, "classic");
+    assert_eq!(query_values(&server, "method").await, ["track.gettags"]);
+    assert_eq!(query_values(&server, "artist").await, ["Cher"]);
+    assert_eq!(query_values(&server, "track").await, ["Believe"]);
+    assert_eq!(query_values(&server, "user").await, ["rj"]);
+}
+
+#[tokio::test]
+async fn no_tags_decode_as_none() {
+    let server = MockServer::start().await;
+    let body = json!({ "tags": { "#text": "\n", "artist": "Cher", "album": "Believe" } });
+    serve(&server, ResponseTemplate::new(200).set_body_json(body), 1).await;
+
+    let tags = public_client(&server)
+        .album_get_tags(("Cher", "Believe"), "rj")
+        .await
+        .unwrap();
+
+    assert!(tags.is_empty());
+    assert_eq!(query_values(&server, "album").await, ["Believe"]);
+}